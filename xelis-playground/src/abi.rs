@@ -0,0 +1,123 @@
+use xelis_abi::Abi;
+use xelis_bytecode::Module;
+use xelis_types::Type;
+
+use crate::{Entry, Parameter};
+
+// Split a list of types on the commas that are not nested
+fn split_type_list(value: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0i32;
+    let mut start = 0;
+
+    for (i, ch) in value.char_indices() {
+        match ch {
+            '<' | '(' | '[' => depth += 1,
+            '>' | ')' | ']' => depth -= 1,
+            ',' if depth == 0 => {
+                parts.push(value[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+
+    let last = value[start..].trim();
+    if !last.is_empty() {
+        parts.push(last);
+    }
+
+    parts
+}
+
+// Parse a type written as displayed by the compiler
+// Named types (structs, enums and opaques) are resolved by `named`
+pub fn parse_type_name(name: &str, named: &dyn Fn(&str) -> Option<Type>) -> Option<Type> {
+    let name = name.trim();
+    if let Some(inner) = name.strip_suffix("[]") {
+        return parse_type_name(inner, named).map(|ty| Type::Array(Box::new(ty)));
+    }
+
+    if let Some((container, inner)) = name.strip_suffix('>').and_then(|n| n.split_once('<')) {
+        let inner = split_type_list(inner);
+        return match (container.trim(), inner.as_slice()) {
+            ("optional", [ty]) => Some(Type::Optional(Box::new(parse_type_name(ty, named)?))),
+            ("range", [ty]) => Some(Type::Range(Box::new(parse_type_name(ty, named)?))),
+            ("map", [key, value]) => Some(Type::Map(
+                Box::new(parse_type_name(key, named)?),
+                Box::new(parse_type_name(value, named)?),
+            )),
+            _ => None,
+        };
+    }
+
+    if let Some(inner) = name.strip_prefix('(').and_then(|n| n.strip_suffix(')')) {
+        return split_type_list(inner)
+            .into_iter()
+            .map(|ty| parse_type_name(ty, named))
+            .collect::<Option<Vec<_>>>()
+            .map(Type::Tuples);
+    }
+
+    Some(match name {
+        "u8" => Type::U8,
+        "u16" => Type::U16,
+        "u32" => Type::U32,
+        "u64" => Type::U64,
+        "u128" => Type::U128,
+        "u256" => Type::U256,
+        "string" => Type::String,
+        "bool" => Type::Bool,
+        "bytes" => Type::Bytes,
+        "any" => Type::Any,
+        _ => return named(name),
+    })
+}
+
+// Recover the entries described by an ABI generated by the compiler
+// Structs and enums are looked up in the module, opaque types with `opaque`
+pub fn entries_from_abi(abi: &str, module: &Module, opaque: &dyn Fn(&str) -> Option<Type>) -> Result<Vec<Entry>, String> {
+    let abi: Abi = serde_json::from_str(abi)
+        .map_err(|e| format!("Invalid ABI: {}", e))?;
+
+    let named = |name: &str| {
+        module.structs()
+            .iter()
+            .find(|ty| ty.name() == name)
+            .map(|ty| Type::Struct(ty.clone()))
+            .or_else(|| module.enums()
+                .iter()
+                .find(|ty| ty.name() == name)
+                .map(|ty| Type::Enum(ty.clone())))
+            .or_else(|| opaque(name))
+    };
+
+    let mut entries = Vec::new();
+    // Hooks are not callable by the users
+    for function in abi.data.iter().filter(|function| function.kind == "entry") {
+        let parameters = function.params
+            .iter()
+            .map(|param| {
+                let ty = parse_type_name(&param.type_name, &named)
+                    .ok_or_else(|| format!("Unsupported type `{}` for parameter `{}` of entry `{}`", param.type_name, param.name, function.name))?;
+
+                Ok(Parameter {
+                    name: param.name.clone(),
+                    ty,
+                })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        entries.push(Entry {
+            id: entries.len(),
+            chunk_id: function.chunk_id,
+            name: function.name.clone(),
+            parameters,
+            return_type: function.outputs.clone().unwrap_or_else(|| "u64".to_owned()),
+            doc: None,
+            executable: true,
+        });
+    }
+
+    Ok(entries)
+}
@@ -0,0 +1,492 @@
+// Language server for Silex speaking LSP over stdio
+// Settings are read from the initialization options and the workspace configuration:
+// `{ "silex": { "contractVersion": 0 } }`
+
+#[cfg(not(target_arch = "wasm32"))]
+mod server {
+    use std::{
+        collections::HashMap,
+        io::{self, BufRead, Read, Write},
+    };
+
+    use serde_json::{json, Value};
+    use xelis_playground::{Diagnostic, DiagnosticSeverity, OutlineItem, Silex, Span};
+
+    // Semantic token types advertised to the client, in the order of the legend
+    const TOKEN_TYPES: &[(&str, &str)] = &[
+        ("local", "variable"),
+        ("parameter", "parameter"),
+        ("function", "function"),
+        ("entry", "function"),
+        ("hook", "event"),
+        ("struct", "struct"),
+        ("enum", "enum"),
+        ("variant", "enumMember"),
+        ("field", "property"),
+        ("constant", "variable"),
+        ("import", "namespace"),
+        ("builtin_function", "function"),
+        ("builtin_type", "type"),
+        ("opaque_type", "interface"),
+    ];
+
+    const TOKEN_MODIFIERS: &[&str] = &["declaration", "readonly", "defaultLibrary"];
+
+    // Byte offset of an LSP position, whose character is counted in UTF-16 code units
+    fn offset_at(text: &str, position: &Value) -> usize {
+        let line = position["line"].as_u64().unwrap_or(0) as usize;
+        let character = position["character"].as_u64().unwrap_or(0) as usize;
+
+        let line_start = if line == 0 {
+            0
+        } else {
+            match text.match_indices('\n').nth(line - 1) {
+                Some((i, _)) => i + 1,
+                None => return text.len(),
+            }
+        };
+
+        let mut units = 0;
+        for (i, c) in text[line_start..].char_indices() {
+            if units >= character || c == '\n' {
+                return line_start + i;
+            }
+            units += c.len_utf16();
+        }
+
+        text.len()
+    }
+
+    fn position(text: &str, offset: usize) -> Value {
+        let offset = offset.min(text.len());
+        let before = &text[..offset];
+        let line = before.matches('\n').count();
+        let line_start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+        let character: usize = before[line_start..].chars().map(char::len_utf16).sum();
+
+        json!({ "line": line, "character": character })
+    }
+
+    fn range(text: &str, span: &Span) -> Value {
+        json!({
+            "start": position(text, span.start_offset()),
+            "end": position(text, span.end_offset()),
+        })
+    }
+
+    fn utf16_len(text: &str, span: &Span) -> usize {
+        text[span.start_offset()..span.end_offset()]
+            .chars()
+            .map(char::len_utf16)
+            .sum()
+    }
+
+    fn diagnostic(text: &str, diagnostic: &Diagnostic) -> Value {
+        let range = diagnostic.span()
+            .map(|span| range(text, &span))
+            .unwrap_or_else(|| json!({ "start": position(text, 0), "end": position(text, 0) }));
+
+        let severity = match diagnostic.severity() {
+            DiagnosticSeverity::Error => 1,
+            DiagnosticSeverity::Warning => 2,
+            DiagnosticSeverity::Info => 3,
+        };
+
+        json!({
+            "range": range,
+            "severity": severity,
+            "code": diagnostic.code(),
+            "source": "silex",
+            "message": diagnostic.message(),
+        })
+    }
+
+    fn symbol_kind(kind: &str) -> u8 {
+        match kind {
+            "import" => 2,
+            "field" => 8,
+            "enum" => 10,
+            "constant" => 14,
+            "variant" => 22,
+            "struct" => 23,
+            "hook" => 24,
+            _ => 12,
+        }
+    }
+
+    fn completion_kind(kind: &str) -> u8 {
+        match kind {
+            "field" => 5,
+            "local" | "variable" | "parameter" => 6,
+            "import" => 9,
+            "enum" => 13,
+            "keyword" => 14,
+            "variant" => 20,
+            "constant" | "const_function" => 21,
+            "struct" => 22,
+            _ => 3,
+        }
+    }
+
+    fn document_symbol(text: &str, item: &OutlineItem) -> Value {
+        json!({
+            "name": item.name(),
+            "detail": item.signature(),
+            "kind": symbol_kind(&item.kind()),
+            "range": range(text, &item.span()),
+            "selectionRange": range(text, &item.name_span()),
+            "children": item.children()
+                .iter()
+                .map(|child| document_symbol(text, child))
+                .collect::<Vec<_>>(),
+        })
+    }
+
+    fn capabilities() -> Value {
+        json!({
+            "capabilities": {
+                // Full document sync
+                "textDocumentSync": 1,
+                "hoverProvider": true,
+                "definitionProvider": true,
+                "referencesProvider": true,
+                "documentSymbolProvider": true,
+                "documentFormattingProvider": true,
+                "renameProvider": true,
+                "completionProvider": { "triggerCharacters": [".", ":"] },
+                "signatureHelpProvider": { "triggerCharacters": ["(", ","] },
+                "semanticTokensProvider": {
+                    "legend": {
+                        "tokenTypes": TOKEN_TYPES.iter().map(|(_, lsp)| *lsp).collect::<Vec<_>>(),
+                        "tokenModifiers": TOKEN_MODIFIERS,
+                    },
+                    "full": true,
+                },
+            },
+            "serverInfo": { "name": "silex-lsp" },
+        })
+    }
+
+    struct Server {
+        silex: Silex,
+        documents: HashMap<String, String>,
+        shutdown: bool,
+    }
+
+    impl Server {
+        fn send(&self, message: Value) -> io::Result<()> {
+            let body = message.to_string();
+            let mut stdout = io::stdout().lock();
+            write!(stdout, "Content-Length: {}\r\n\r\n{}", body.len(), body)?;
+            stdout.flush()
+        }
+
+        fn respond(&self, id: Value, result: Value) -> io::Result<()> {
+            self.send(json!({ "jsonrpc": "2.0", "id": id, "result": result }))
+        }
+
+        fn respond_error(&self, id: Value, code: i32, message: &str) -> io::Result<()> {
+            self.send(json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } }))
+        }
+
+        fn notify(&self, method: &str, params: Value) -> io::Result<()> {
+            self.send(json!({ "jsonrpc": "2.0", "method": method, "params": params }))
+        }
+
+        fn publish_diagnostics(&self, uri: &str) -> io::Result<()> {
+            let diagnostics: Vec<Value> = match self.documents.get(uri) {
+                Some(text) => self.silex
+                    .compile_with_diagnostics(text)
+                    .diagnostics()
+                    .iter()
+                    .map(|d| diagnostic(text, d))
+                    .collect(),
+                None => Vec::new(),
+            };
+
+            self.notify("textDocument/publishDiagnostics", json!({ "uri": uri, "diagnostics": diagnostics }))
+        }
+
+        // Select the contract version from the settings, returns true if it changed
+        fn apply_settings(&mut self, settings: &Value) -> bool {
+            let settings = settings.get("silex").unwrap_or(settings);
+            let Some(version) = settings.get("contractVersion").and_then(Value::as_u64) else {
+                return false;
+            };
+
+            let Ok(version) = u8::try_from(version) else {
+                eprintln!("Invalid contract version {}", version);
+                return false;
+            };
+
+            if version == self.silex.get_contract_version() {
+                return false;
+            }
+
+            if !self.silex.available_contract_versions().contains(&version) {
+                eprintln!("Unknown contract version {}", version);
+                return false;
+            }
+
+            self.silex.set_contract_version(version).is_ok()
+        }
+
+        fn document(&self, params: &Value) -> Option<(String, &str)> {
+            let uri = params["textDocument"]["uri"].as_str()?;
+            let text = self.documents.get(uri)?;
+            Some((uri.to_owned(), text.as_str()))
+        }
+
+        fn request(&self, method: &str, params: &Value) -> Option<Value> {
+            let (uri, text) = self.document(params)?;
+            let offset = offset_at(text, &params["position"]);
+
+            let result = match method {
+                "textDocument/hover" => {
+                    let hover = self.silex.hover(text, offset)?;
+                    json!({
+                        "contents": { "kind": "markdown", "value": hover.markdown() },
+                        "range": range(text, &hover.span()),
+                    })
+                }
+                "textDocument/definition" => {
+                    let span = self.silex.definition(text, offset)?.span()?;
+                    json!({ "uri": uri, "range": range(text, &span) })
+                }
+                "textDocument/references" => {
+                    let include_declaration = params["context"]["includeDeclaration"].as_bool().unwrap_or(true);
+                    let locations: Vec<Value> = self.silex
+                        .references(text, offset, include_declaration)
+                        .iter()
+                        .map(|span| json!({ "uri": uri, "range": range(text, span) }))
+                        .collect();
+                    json!(locations)
+                }
+                "textDocument/documentSymbol" => {
+                    let symbols: Vec<Value> = self.silex
+                        .outline(text)
+                        .iter()
+                        .map(|item| document_symbol(text, item))
+                        .collect();
+                    json!(symbols)
+                }
+                "textDocument/completion" => {
+                    let items: Vec<Value> = self.silex
+                        .complete(text, offset)
+                        .iter()
+                        .map(|item| json!({
+                            "label": item.label(),
+                            "kind": completion_kind(&item.kind()),
+                            "detail": item.detail(),
+                            "documentation": item.doc(),
+                            "insertText": item.insert_text(),
+                        }))
+                        .collect();
+                    json!(items)
+                }
+                "textDocument/signatureHelp" => {
+                    let help = self.silex.signature_help(text, offset)?;
+                    json!({
+                        "signatures": [{
+                            "label": help.signature(),
+                            "documentation": help.doc(),
+                            "parameters": help.parameters()
+                                .into_iter()
+                                .map(|p| json!({ "label": p }))
+                                .collect::<Vec<_>>(),
+                        }],
+                        "activeSignature": 0,
+                        "activeParameter": help.active_parameter(),
+                    })
+                }
+                "textDocument/formatting" => {
+                    let formatted = match self.silex.format_code(text) {
+                        Ok(formatted) => formatted,
+                        Err(err) => {
+                            eprintln!("Formatting failed: {}", err);
+                            return None;
+                        }
+                    };
+
+                    json!([{
+                        "range": { "start": position(text, 0), "end": position(text, text.len()) },
+                        "newText": formatted,
+                    }])
+                }
+                "textDocument/semanticTokens/full" => json!({ "data": self.semantic_tokens(text) }),
+                _ => return None,
+            };
+
+            Some(result)
+        }
+
+        // Tokens encoded relatively to the previous one as required by the protocol
+        fn semantic_tokens(&self, text: &str) -> Vec<u32> {
+            let mut data = Vec::new();
+            let (mut last_line, mut last_character) = (0, 0);
+
+            for token in self.silex.semantic_tokens(text) {
+                let span = token.span();
+                let token_type = token.token_type();
+                let Some(type_index) = TOKEN_TYPES.iter().position(|(name, _)| *name == token_type) else {
+                    continue;
+                };
+
+                let start = position(text, span.start_offset());
+                let line = start["line"].as_u64().unwrap_or(0) as u32;
+                let character = start["character"].as_u64().unwrap_or(0) as u32;
+
+                let mut modifiers = 0u32;
+                if token.is_declaration() {
+                    modifiers |= 1;
+                }
+                if token_type == "constant" {
+                    modifiers |= 1 << 1;
+                }
+                if matches!(token_type.as_str(), "builtin_function" | "builtin_type" | "opaque_type") {
+                    modifiers |= 1 << 2;
+                }
+
+                let delta_character = if line == last_line { character - last_character } else { character };
+                data.extend([line - last_line, delta_character, utf16_len(text, &span) as u32, type_index as u32, modifiers]);
+                last_line = line;
+                last_character = character;
+            }
+
+            data
+        }
+
+        fn rename(&self, params: &Value) -> Result<Value, String> {
+            let (uri, text) = self.document(params).ok_or("Unknown document")?;
+            let offset = offset_at(text, &params["position"]);
+            let new_name = params["newName"].as_str().ok_or("Missing new name")?;
+
+            let edits: Vec<Value> = self.silex
+                .rename_edits(text, offset, new_name)?
+                .iter()
+                .map(|edit| json!({ "range": range(text, &edit.span()), "newText": edit.new_text() }))
+                .collect();
+
+            Ok(json!({ "changes": { uri: edits } }))
+        }
+
+        fn handle(&mut self, message: Value) -> io::Result<()> {
+            let method = message["method"].as_str().unwrap_or_default();
+            let params = &message["params"];
+            let id = message.get("id").cloned();
+
+            match (method, id) {
+                ("initialize", Some(id)) => {
+                    self.apply_settings(&params["initializationOptions"]);
+                    self.respond(id, capabilities())
+                }
+                ("shutdown", Some(id)) => {
+                    self.shutdown = true;
+                    self.respond(id, Value::Null)
+                }
+                ("exit", _) => std::process::exit(if self.shutdown { 0 } else { 1 }),
+                ("workspace/didChangeConfiguration", None) => {
+                    if self.apply_settings(&params["settings"]) {
+                        let uris: Vec<String> = self.documents.keys().cloned().collect();
+                        for uri in uris {
+                            self.publish_diagnostics(&uri)?;
+                        }
+                    }
+                    Ok(())
+                }
+                ("textDocument/didOpen", None) => {
+                    let document = &params["textDocument"];
+                    let uri = document["uri"].as_str().unwrap_or_default().to_owned();
+                    let text = document["text"].as_str().unwrap_or_default().to_owned();
+                    self.documents.insert(uri.clone(), text);
+                    self.publish_diagnostics(&uri)
+                }
+                ("textDocument/didChange", None) => {
+                    let uri = params["textDocument"]["uri"].as_str().unwrap_or_default().to_owned();
+                    // Full sync: the last change contains the whole document
+                    let text = params["contentChanges"]
+                        .as_array()
+                        .and_then(|changes| changes.last())
+                        .and_then(|change| change["text"].as_str());
+
+                    if let Some(text) = text {
+                        self.documents.insert(uri.clone(), text.to_owned());
+                    }
+                    self.publish_diagnostics(&uri)
+                }
+                ("textDocument/didClose", None) => {
+                    let uri = params["textDocument"]["uri"].as_str().unwrap_or_default().to_owned();
+                    self.documents.remove(&uri);
+                    self.publish_diagnostics(&uri)
+                }
+                ("textDocument/rename", Some(id)) => match self.rename(params) {
+                    Ok(edit) => self.respond(id, edit),
+                    // RequestFailed
+                    Err(err) => self.respond_error(id, -32803, &err),
+                },
+                (method, Some(id)) if method.starts_with("textDocument/") => {
+                    let result = self.request(method, params).unwrap_or(Value::Null);
+                    self.respond(id, result)
+                }
+                // MethodNotFound
+                (_, Some(id)) => self.respond_error(id, -32601, &format!("Unsupported method {}", method)),
+                // Other notifications are ignored
+                (_, None) => Ok(()),
+            }
+        }
+    }
+
+    // Read a message framed by its Content-Length header
+    fn read_message(input: &mut impl BufRead) -> io::Result<Option<Value>> {
+        let mut length = None;
+        loop {
+            let mut header = String::new();
+            if input.read_line(&mut header)? == 0 {
+                return Ok(None);
+            }
+
+            let header = header.trim_end();
+            if header.is_empty() {
+                break;
+            }
+
+            if let Some(value) = header.strip_prefix("Content-Length:") {
+                length = value.trim().parse::<usize>().ok();
+            }
+        }
+
+        let Some(length) = length else {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "Missing Content-Length header"));
+        };
+
+        let mut body = vec![0; length];
+        input.read_exact(&mut body)?;
+        serde_json::from_slice(&body)
+            .map(Some)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+
+    pub fn run() -> io::Result<()> {
+        let mut server = Server {
+            silex: Silex::new(),
+            documents: HashMap::new(),
+            shutdown: false,
+        };
+
+        let stdin = io::stdin();
+        let mut input = stdin.lock();
+        while let Some(message) = read_message(&mut input)? {
+            server.handle(message)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn main() -> std::io::Result<()> {
+    server::run()
+}
+
+#[cfg(target_arch = "wasm32")]
+fn main() {}
@@ -0,0 +1,398 @@
+// Command line interface for CI and scripts
+// silex [--contract-version <n>] compile <file> [--format bytes|hex|json|asm|abi] [--output <file>]
+// silex [--contract-version <n>] run <file> --entry <name|index> [--param <value>]... [--storage <presets.json>]
+//       [--deposit <asset>=<amount>]... [--max-gas <n>] [--constructor]
+// silex [--contract-version <n>] test <file> [--max-gas <n>]
+// silex [--contract-version <n>] verify <file>
+// silex [--contract-version <n>] deploy <file> [--deposit <asset>=<amount>]... [--max-gas <n>]
+// Results and errors are printed as JSON, the process exits with 1 on any error
+
+#[cfg(not(target_arch = "wasm32"))]
+mod cli {
+    use std::{collections::HashMap, fs, io::Write};
+
+    use serde_json::{json, Value};
+    use xelis_playground::{Diagnostic, Program, Silex, StoragePresetJSON};
+
+    const USAGE: &str = "usage: silex [--contract-version <n>] <compile|run|test|verify|deploy> <file> [options]";
+
+    // Arguments left once the options are taken out
+    struct Arguments {
+        positional: Vec<String>,
+        options: HashMap<String, Vec<String>>,
+        flags: Vec<String>,
+    }
+
+    impl Arguments {
+        fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+            let mut arguments = Arguments {
+                positional: Vec::new(),
+                options: HashMap::new(),
+                flags: Vec::new(),
+            };
+
+            while let Some(arg) = args.next() {
+                match arg.as_str() {
+                    "--constructor" => arguments.flags.push(arg),
+                    option if option.starts_with("--") => {
+                        let value = args.next()
+                            .ok_or_else(|| format!("Missing value for {}", option))?;
+                        arguments.options.entry(option[2..].to_owned()).or_default().push(value);
+                    }
+                    _ => arguments.positional.push(arg),
+                }
+            }
+
+            Ok(arguments)
+        }
+
+        fn option(&self, name: &str) -> Option<&str> {
+            self.options
+                .get(name)
+                .and_then(|values| values.last())
+                .map(String::as_str)
+        }
+
+        fn options(&self, name: &str) -> &[String] {
+            self.options
+                .get(name)
+                .map(Vec::as_slice)
+                .unwrap_or_default()
+        }
+
+        fn flag(&self, name: &str) -> bool {
+            self.flags.iter().any(|flag| flag[2..] == *name)
+        }
+
+        fn deposits(&self) -> Result<HashMap<String, String>, String> {
+            self.options("deposit")
+                .iter()
+                .map(|deposit| deposit.split_once('=')
+                    .map(|(asset, amount)| (asset.to_owned(), amount.to_owned()))
+                    .ok_or_else(|| format!("Invalid deposit, expected <asset>=<amount>: {}", deposit)))
+                .collect()
+        }
+
+        fn max_gas(&self) -> Result<Option<u64>, String> {
+            self.option("max-gas")
+                .map(|value| value.parse().map_err(|_| format!("Invalid max gas: {}", value)))
+                .transpose()
+        }
+    }
+
+    fn diagnostic(diagnostic: &Diagnostic) -> Value {
+        json!({
+            "severity": diagnostic.severity_name(),
+            "phase": diagnostic.phase_name(),
+            "code": diagnostic.code(),
+            "message": diagnostic.message(),
+            "file": diagnostic.file(),
+            "start_line": diagnostic.start_line(),
+            "start_column": diagnostic.start_column(),
+            "end_line": diagnostic.end_line(),
+            "end_column": diagnostic.end_column(),
+        })
+    }
+
+    fn read(path: &str) -> Result<String, String> {
+        fs::read_to_string(path)
+            .map_err(|err| format!("Failed to read {}: {}", path, err))
+    }
+
+    // Program compiled from the file, or the diagnostics explaining why it failed
+    fn compile(silex: &Silex, path: &str) -> Result<Result<Program, Value>, String> {
+        let code = read(path)?;
+        let result = silex.compile_with_diagnostics(&code);
+        let diagnostics: Vec<Value> = result.diagnostics()
+            .iter()
+            .map(|d| diagnostic(&d.clone().with_file(path)))
+            .collect();
+
+        Ok(match result.program() {
+            Some(program) => {
+                for warning in program.warnings() {
+                    eprintln!("{}", diagnostic(&warning.with_file(path)));
+                }
+                Ok(program)
+            }
+            None => Err(json!({ "success": false, "diagnostics": diagnostics })),
+        })
+    }
+
+    fn entry_index(program: &Program, entry: &str) -> Result<usize, String> {
+        let entries = program.entries();
+        entries.iter()
+            .position(|e| e.name() == entry)
+            .or_else(|| entry.parse().ok().filter(|i| *i < entries.len()))
+            .ok_or_else(|| format!("Unknown entry: {}", entry))
+    }
+
+    fn compile_command(silex: &Silex, arguments: &Arguments, path: &str) -> Result<bool, String> {
+        let program = match compile(silex, path)? {
+            Ok(program) => program,
+            Err(failure) => {
+                println!("{}", failure);
+                return Ok(false);
+            }
+        };
+
+        let output = match arguments.option("format").unwrap_or("hex") {
+            "bytes" => program.to_bytes(),
+            "hex" => program.to_hex().into_bytes(),
+            "json" => program.to_json().into_bytes(),
+            "asm" => program.to_asm().into_bytes(),
+            "abi" => program.abi_text()
+                .ok_or_else(|| "No ABI available for this program".to_owned())?
+                .into_bytes(),
+            format => return Err(format!("Unknown format: {}", format)),
+        };
+
+        match arguments.option("output") {
+            Some(output_path) => fs::write(output_path, output)
+                .map_err(|err| format!("Failed to write {}: {}", output_path, err))?,
+            None => {
+                let mut stdout = std::io::stdout();
+                stdout.write_all(&output)
+                    .and_then(|_| stdout.flush())
+                    .map_err(|err| err.to_string())?;
+            }
+        }
+
+        Ok(true)
+    }
+
+    async fn run_command(silex: &Silex, arguments: &Arguments, path: &str) -> Result<bool, String> {
+        let program = match compile(silex, path)? {
+            Ok(program) => program,
+            Err(failure) => {
+                println!("{}", failure);
+                return Ok(false);
+            }
+        };
+
+        let entry = arguments.option("entry")
+            .ok_or_else(|| "Missing --entry".to_owned())?;
+        let entry_id = entry_index(&program, entry)?;
+
+        let storage_presets: Vec<StoragePresetJSON> = match arguments.option("storage") {
+            Some(presets_path) => serde_json::from_str(&read(presets_path)?)
+                .map_err(|err| format!("Invalid storage presets: {}", err))?,
+            None => Vec::new(),
+        };
+
+        let result = silex.execute_entry(
+            program,
+            entry_id,
+            arguments.max_gas()?,
+            arguments.options("param"),
+            storage_presets,
+            arguments.deposits()?,
+            arguments.flag("constructor"),
+        ).await?;
+
+        println!("{}", result.to_json());
+        Ok(!result.is_error())
+    }
+
+    // Every entry named `test*` without parameters must return 0
+    async fn test_command(silex: &Silex, arguments: &Arguments, path: &str) -> Result<bool, String> {
+        let program = match compile(silex, path)? {
+            Ok(program) => program,
+            Err(failure) => {
+                println!("{}", failure);
+                return Ok(false);
+            }
+        };
+
+        let max_gas = arguments.max_gas()?;
+        let mut tests = Vec::new();
+        let mut failed = 0;
+        for (entry_id, entry) in program.entries().iter().enumerate() {
+            if !entry.name().starts_with("test") || !entry.parameters().is_empty() {
+                continue;
+            }
+
+            let result = silex.execute_entry(
+                program.clone(),
+                entry_id,
+                max_gas,
+                &[],
+                Vec::new(),
+                HashMap::new(),
+                program.has_constructor(),
+            ).await;
+
+            let (passed, result) = match result {
+                Ok(result) => (!result.is_error() && result.value() == "0", serde_json::from_str(&result.to_json()).unwrap_or(Value::Null)),
+                Err(err) => (false, json!({ "value": err, "is_error": true })),
+            };
+
+            if !passed {
+                failed += 1;
+            }
+
+            tests.push(json!({
+                "name": entry.name(),
+                "passed": passed,
+                "result": result,
+            }));
+        }
+
+        println!("{}", json!({
+            "success": failed == 0,
+            "passed": tests.len() - failed,
+            "failed": failed,
+            "tests": tests,
+        }));
+
+        Ok(failed == 0)
+    }
+
+    // Static checks of the compiled module, warnings don't fail the command
+    fn verify_command(silex: &Silex, path: &str) -> Result<bool, String> {
+        let program = match compile(silex, path)? {
+            Ok(program) => program,
+            Err(failure) => {
+                println!("{}", failure);
+                return Ok(false);
+            }
+        };
+
+        let report = program.verify();
+        println!("{}", report.to_json());
+        Ok(report.is_valid())
+    }
+
+    // Payload of the deploy transaction, to be signed by a wallet
+    fn deploy_command(silex: &Silex, arguments: &Arguments, path: &str) -> Result<bool, String> {
+        let program = match compile(silex, path)? {
+            Ok(program) => program,
+            Err(failure) => {
+                println!("{}", failure);
+                return Ok(false);
+            }
+        };
+
+        let payload = program.build_deploy_payload(silex.get_contract_version(), arguments.max_gas()?, arguments.deposits()?)?;
+        println!("{}", json!({
+            "version": payload.version(),
+            "has_constructor": payload.has_constructor(),
+            "hex": payload.to_hex(),
+            "payload": serde_json::from_str::<Value>(&payload.to_json()).unwrap_or(Value::Null),
+        }));
+
+        Ok(true)
+    }
+
+    async fn execute(arguments: Arguments) -> Result<bool, String> {
+        let mut silex = Silex::new();
+        if let Some(version) = arguments.option("contract-version") {
+            let version: u8 = version.parse()
+                .ok()
+                .filter(|v| silex.available_contract_versions().contains(v))
+                .ok_or_else(|| format!("Invalid contract version: {}", version))?;
+
+            silex.set_contract_version(version)
+                .map_err(|_| format!("Invalid contract version: {}", version))?;
+        }
+
+        let [command, path] = arguments.positional.as_slice() else {
+            return Err(USAGE.to_owned());
+        };
+
+        match command.as_str() {
+            "compile" => compile_command(&silex, &arguments, path),
+            "run" => run_command(&silex, &arguments, path).await,
+            "test" => test_command(&silex, &arguments, path).await,
+            "verify" => verify_command(&silex, path),
+            "deploy" => deploy_command(&silex, &arguments, path),
+            _ => Err(USAGE.to_owned()),
+        }
+    }
+
+    // Exit code of the command line made of the arguments
+    pub fn run(args: impl Iterator<Item = String>) -> i32 {
+        let runtime = match tokio::runtime::Builder::new_current_thread().enable_all().build() {
+            Ok(runtime) => runtime,
+            Err(err) => {
+                println!("{}", json!({ "success": false, "error": err.to_string() }));
+                return 1;
+            }
+        };
+
+        let result = Arguments::parse(args)
+            .and_then(|arguments| runtime.block_on(execute(arguments)));
+
+        match result {
+            Ok(true) => 0,
+            Ok(false) => 1,
+            Err(err) => {
+                println!("{}", json!({ "success": false, "error": err }));
+                1
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use std::path::PathBuf;
+
+        use super::*;
+
+        fn args(args: &[&str]) -> impl Iterator<Item = String> {
+            args.iter()
+                .map(|arg| arg.to_string())
+                .collect::<Vec<_>>()
+                .into_iter()
+        }
+
+        // Source file written in the temporary directory
+        fn source(name: &str, code: &str) -> PathBuf {
+            let path = std::env::temp_dir().join(format!("silex-cli-{}-{}.slx", std::process::id(), name));
+            fs::write(&path, code).expect("Failed to write the source file");
+            path
+        }
+
+        #[test]
+        fn test_parse_arguments() {
+            let arguments = Arguments::parse(args(&[
+                "--contract-version", "0", "run", "main.slx", "--entry", "main",
+                "--param", "1", "--param", "2", "--constructor", "--deposit", "XEL=10",
+            ])).expect("Failed to parse the arguments");
+
+            assert_eq!(arguments.positional, vec!["run", "main.slx"]);
+            assert_eq!(arguments.option("contract-version"), Some("0"));
+            assert_eq!(arguments.option("entry"), Some("main"));
+            assert_eq!(arguments.options("param"), ["1", "2"]);
+            assert!(arguments.flag("constructor"));
+            assert_eq!(arguments.deposits().unwrap().get("XEL").map(String::as_str), Some("10"));
+            assert_eq!(arguments.max_gas(), Ok(None));
+
+            assert!(Arguments::parse(args(&["run", "main.slx", "--entry"])).is_err());
+        }
+
+        #[test]
+        fn test_exit_codes() {
+            let path = source("exit-codes", "entry main() {\n    return 0;\n}\n");
+            let path = path.to_str().unwrap();
+
+            assert_eq!(run(args(&["compile", path, "--format", "abi"])), 0);
+            assert_eq!(run(args(&["compile", path, "--format", "xml"])), 1);
+            assert_eq!(run(args(&["run", path])), 1);
+            assert_eq!(run(args(&["run", path, "--entry", "main"])), 0);
+            assert_eq!(run(args(&["run", path, "--entry", "missing"])), 1);
+            assert_eq!(run(args(&["unknown", path])), 1);
+            assert_eq!(run(args(&["compile"])), 1);
+
+            fs::remove_file(path).ok();
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn main() {
+    std::process::exit(cli::run(std::env::args().skip(1)));
+}
+
+#[cfg(target_arch = "wasm32")]
+fn main() {}
@@ -0,0 +1,47 @@
+// Functions provided by the environment of the selected contract version
+
+use std::collections::HashSet;
+
+use crate::{ConstFunc, Func, symbols::Target};
+
+pub struct Environment {
+    pub functions: Vec<Func>,
+    pub const_functions: Vec<ConstFunc>,
+    // Names of the opaque types, such as `Hash`
+    pub opaque_types: HashSet<String>,
+}
+
+// Whether a function declared on a type of the environment applies to a value type
+// Generic types are written with parameters, only their shape is compared
+pub fn type_matches(on_type: &str, ty: &str) -> bool {
+    let shape = |t: &str| -> String {
+        let t = t.trim();
+        if t.ends_with("[]") {
+            "[]".to_owned()
+        } else {
+            t.split('<').next().unwrap_or(t).to_owned()
+        }
+    };
+
+    on_type == ty || shape(on_type) == shape(ty)
+}
+
+impl Environment {
+    // Function an occurrence refers to
+    pub fn function(&self, target: &Target) -> Option<&Func> {
+        match target {
+            Target::Function { name, on_type } => self.functions
+                .iter()
+                .find(|f| &f.name == name && f.on_type == *on_type && !f.on_instance),
+            Target::Method { name, receiver } => {
+                // Without the receiver type, the function can't be told apart
+                // from the ones with the same name declared on other types
+                let ty = receiver.as_deref()?;
+                self.functions
+                    .iter()
+                    .find(|f| &f.name == name && f.on_instance && f.on_type.as_deref().map(|t| type_matches(t, ty)).unwrap_or(false))
+            }
+            _ => None,
+        }
+    }
+}
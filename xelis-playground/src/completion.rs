@@ -0,0 +1,268 @@
+// Code completion
+// The context is found from the tokens before the cursor: a member access after `.`,
+// a path after `::`, or any name visible at the cursor otherwise.
+
+use std::collections::HashSet;
+
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use crate::{
+    builtins::{type_matches, Environment},
+    symbols::{element_type, SymbolIndex, SymbolKind, Target},
+    syntax::{self, SyntaxToken, TokenKind, KEYWORDS},
+    Func,
+};
+
+#[wasm_bindgen]
+#[derive(Debug, Clone)]
+pub struct CompletionItem {
+    label: String,
+    // Symbol kind, `builtin`, `const_function` or `keyword`
+    kind: String,
+    detail: Option<String>,
+    doc: Option<String>,
+    insert_text: String,
+}
+
+#[wasm_bindgen]
+impl CompletionItem {
+    pub fn label(&self) -> String {
+        self.label.clone()
+    }
+
+    pub fn kind(&self) -> String {
+        self.kind.clone()
+    }
+
+    // Signature or type of the suggestion
+    pub fn detail(&self) -> Option<String> {
+        self.detail.clone()
+    }
+
+    pub fn doc(&self) -> Option<String> {
+        self.doc.clone()
+    }
+
+    pub fn insert_text(&self) -> String {
+        self.insert_text.clone()
+    }
+}
+
+fn call_text(name: &str, has_params: bool) -> String {
+    if has_params {
+        format!("{}(", name)
+    } else {
+        format!("{}()", name)
+    }
+}
+
+fn builtin_item(function: &Func) -> CompletionItem {
+    CompletionItem {
+        label: function.name.clone(),
+        kind: "builtin".to_owned(),
+        detail: Some(function.signature()),
+        doc: function.comment.clone(),
+        insert_text: call_text(&function.name, !function.params.is_empty()),
+    }
+}
+
+fn symbol_item(index: &SymbolIndex, id: usize) -> CompletionItem {
+    let symbol = &index.symbols[id];
+    let (detail, insert_text) = match symbol.kind {
+        kind if kind.is_callable() => {
+            (Some(index.signature(id)), call_text(&symbol.name, !symbol.children.is_empty()))
+        }
+        SymbolKind::Parameter | SymbolKind::Local | SymbolKind::Field => (symbol.ty.clone(), symbol.name.clone()),
+        _ => (Some(index.signature(id)), symbol.name.clone()),
+    };
+
+    CompletionItem {
+        label: symbol.name.clone(),
+        kind: symbol.kind.as_str().to_owned(),
+        detail,
+        doc: symbol.doc.clone(),
+        insert_text,
+    }
+}
+
+// Type of the expression ending with the token at `end`
+fn type_before(tokens: &[SyntaxToken], end: usize, index: &SymbolIndex, env: &Environment) -> Option<String> {
+    let token = tokens.get(end)?;
+    match token.text {
+        ")" | "]" => {
+            // Find the opening delimiter
+            let open_text = if token.text == ")" { "(" } else { "[" };
+            let mut depth = 0i32;
+            let mut open = end;
+            loop {
+                match tokens[open].text {
+                    ")" | "]" => depth += 1,
+                    "(" | "[" => depth -= 1,
+                    _ => {}
+                }
+                if depth == 0 {
+                    break;
+                }
+                open = open.checked_sub(1)?;
+            }
+
+            if tokens[open].text != open_text {
+                return None;
+            }
+
+            let ty = type_before(tokens, open.checked_sub(1)?, index, env)?;
+            if token.text == "]" {
+                element_type(&ty).map(str::to_owned)
+            } else {
+                Some(ty)
+            }
+        }
+        _ => {
+            let occurrence = index.occurrences.iter().find(|o| o.start == token.start)?;
+            match &occurrence.target {
+                Target::Symbol(id) => {
+                    let symbol = &index.symbols[*id];
+                    match symbol.kind {
+                        SymbolKind::Struct | SymbolKind::Enum => Some(symbol.name.clone()),
+                        SymbolKind::Variant => symbol.parent.map(|p| index.symbols[p].name.clone()),
+                        _ => symbol.ty.clone(),
+                    }
+                }
+                target => env.function(target).and_then(|f| f.return_type.clone()),
+            }
+        }
+    }
+}
+
+pub fn complete(index: &SymbolIndex, source: &str, offset: usize, env: &Environment) -> Vec<CompletionItem> {
+    let mut offset = offset.min(source.len());
+    // The offset may fall inside a multi-bytes character
+    while !source.is_char_boundary(offset) {
+        offset -= 1;
+    }
+    let tokens: Vec<SyntaxToken> = syntax::scan(&source[..offset])
+        .into_iter()
+        .filter(|t| !t.is_trivia())
+        .collect();
+
+    // Name being typed, if any
+    let (prefix, before) = match tokens.last() {
+        Some(t) if t.end == offset && matches!(t.kind, TokenKind::Identifier | TokenKind::Keyword | TokenKind::Type) => {
+            (t.text, tokens.len().checked_sub(2))
+        }
+        _ => ("", tokens.len().checked_sub(1)),
+    };
+
+    let mut items = match before.map(|i| (i, tokens[i].text)) {
+        Some((i, ".")) => members(&tokens, i, index, env),
+        Some((i, "::")) => paths(&tokens, i, index, env),
+        _ => visible(index, offset, env),
+    };
+
+    items.retain(|item| item.label.starts_with(prefix));
+    items
+}
+
+// Fields and methods of the receiver before the dot
+fn members(tokens: &[SyntaxToken], dot: usize, index: &SymbolIndex, env: &Environment) -> Vec<CompletionItem> {
+    let Some(ty) = dot.checked_sub(1).and_then(|end| type_before(tokens, end, index, env)) else {
+        return Vec::new();
+    };
+
+    let mut items: Vec<CompletionItem> = index
+        .fields_of_type(&ty)
+        .unwrap_or_default()
+        .iter()
+        .map(|id| symbol_item(index, *id))
+        .collect();
+
+    items.extend(index.methods_of_type(&ty).map(|id| symbol_item(index, id)));
+    items.extend(env.functions
+        .iter()
+        .filter(|f| f.on_instance && f.on_type.as_deref().map(|t| type_matches(t, &ty)).unwrap_or(false))
+        .map(builtin_item));
+
+    items
+}
+
+// Variants, static functions and const functions of the type before `::`
+fn paths(tokens: &[SyntaxToken], colons: usize, index: &SymbolIndex, env: &Environment) -> Vec<CompletionItem> {
+    let Some(name) = colons.checked_sub(1).map(|i| tokens[i].text) else {
+        return Vec::new();
+    };
+
+    let mut items = Vec::new();
+    if let Some(id) = index.find_top_level(name) {
+        let symbol = &index.symbols[id];
+        match symbol.kind {
+            SymbolKind::Enum => items.extend(symbol.children.iter().map(|id| symbol_item(index, *id))),
+            SymbolKind::Struct => items.extend(index
+                .methods_of_type(name)
+                .map(|id| symbol_item(index, id))),
+            _ => {}
+        }
+    }
+
+    items.extend(env.functions
+        .iter()
+        .filter(|f| !f.on_instance && f.on_type.as_deref() == Some(name))
+        .map(builtin_item));
+
+    items.extend(env.const_functions
+        .iter()
+        .filter(|f| f.for_type == name)
+        .map(|f| CompletionItem {
+            label: f.name.clone(),
+            kind: "const_function".to_owned(),
+            detail: Some(format!("const fn {}::{}({})", f.for_type, f.name, f.params.join(", "))),
+            doc: f.comment.clone(),
+            insert_text: call_text(&f.name, !f.params.is_empty()),
+        }));
+
+    items
+}
+
+// Names that can be used at the cursor
+fn visible(index: &SymbolIndex, offset: usize, env: &Environment) -> Vec<CompletionItem> {
+    let function = index
+        .top_level()
+        .find(|(_, s)| s.kind.is_callable() && s.decl_start < offset && offset <= s.decl_end)
+        .map(|(id, _)| id);
+
+    let mut items: Vec<CompletionItem> = index.symbols
+        .iter()
+        .enumerate()
+        .filter(|(_, s)| function.is_some() && s.parent == function)
+        .filter(|(_, s)| match s.kind {
+            SymbolKind::Parameter => true,
+            SymbolKind::Local => s.visible_start <= offset && offset <= s.visible_end,
+            _ => false,
+        })
+        // Most recent declarations first, so shadowed names come last
+        .rev()
+        .map(|(id, _)| symbol_item(index, id))
+        .collect();
+
+    items.extend(index
+        .top_level()
+        .filter(|(_, s)| s.on_type.is_none() && !matches!(s.kind, SymbolKind::Entry | SymbolKind::Hook | SymbolKind::Import))
+        .map(|(id, _)| symbol_item(index, id)));
+
+    items.extend(env.functions
+        .iter()
+        .filter(|f| f.on_type.is_none())
+        .map(builtin_item));
+
+    items.extend(KEYWORDS.iter().map(|keyword| CompletionItem {
+        label: keyword.to_string(),
+        kind: "keyword".to_owned(),
+        detail: None,
+        doc: None,
+        insert_text: keyword.to_string(),
+    }));
+
+    // Keep the closest declaration of a shadowed name
+    let mut seen = HashSet::new();
+    items.retain(|item| seen.insert(item.label.clone()));
+    items
+}
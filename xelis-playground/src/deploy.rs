@@ -0,0 +1,70 @@
+// Payload of the transaction deploying a program, as built by a wallet
+// The contract is registered under the hash of the signed deploy transaction:
+// it can't be known from the payload alone, only once the wallet signed it.
+
+use wasm_bindgen::prelude::wasm_bindgen;
+use xelis_common::{
+    crypto::Hashable,
+    serializer::Serializer,
+    transaction::{Transaction, TransactionType},
+};
+
+#[wasm_bindgen]
+#[derive(Debug, Clone)]
+pub struct DeployPayload {
+    version: u8,
+    // Serialized `TransactionType::DeployContract`
+    bytes: Vec<u8>,
+    json: String,
+    has_constructor: bool,
+}
+
+#[wasm_bindgen]
+impl DeployPayload {
+    // Contract version the program is deployed with
+    pub fn version(&self) -> u8 {
+        self.version
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.bytes.clone()
+    }
+
+    pub fn to_hex(&self) -> String {
+        hex::encode(&self.bytes)
+    }
+
+    pub fn to_json(&self) -> String {
+        self.json.clone()
+    }
+
+    // The constructor is invoked on deploy
+    pub fn has_constructor(&self) -> bool {
+        self.has_constructor
+    }
+
+    // Hash of the deployed contract, given the deploy transaction signed by the wallet
+    pub fn contract_hash(&self, transaction_hex: &str) -> Result<String, String> {
+        let transaction_hex = transaction_hex.trim();
+        let transaction = Transaction::from_hex(transaction_hex.strip_prefix("0x").unwrap_or(transaction_hex))
+            .map_err(|e| format!("Invalid transaction hex: {}", e))?;
+
+        if transaction.get_data().to_bytes() != self.bytes {
+            return Err("The transaction doesn't deploy this payload".to_owned());
+        }
+
+        Ok(transaction.hash().to_hex())
+    }
+}
+
+impl DeployPayload {
+    pub fn new(version: u8, data: &TransactionType, has_constructor: bool) -> Result<Self, String> {
+        Ok(Self {
+            version,
+            bytes: data.to_bytes(),
+            json: serde_json::to_string_pretty(data)
+                .map_err(|e| format!("Failed to serialize the payload: {}", e))?,
+            has_constructor,
+        })
+    }
+}
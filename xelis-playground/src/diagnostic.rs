@@ -0,0 +1,186 @@
+use std::fmt;
+
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use crate::span::Span;
+
+// Compilation step that produced a diagnostic
+// Only the lexer and the parser report where the error is in the source code,
+// the errors of the compiler, of the ABI and of the assembler have no span
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiagnosticPhase {
+    Lex,
+    Parse,
+    Compile,
+    Abi,
+    Assemble,
+}
+
+impl DiagnosticPhase {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Lex => "lex",
+            Self::Parse => "parse",
+            Self::Compile => "compile",
+            Self::Abi => "abi",
+            Self::Assemble => "assemble",
+        }
+    }
+}
+
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiagnosticSeverity {
+    Error,
+    Warning,
+    Info,
+}
+
+impl DiagnosticSeverity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Error => "error",
+            Self::Warning => "warning",
+            Self::Info => "info",
+        }
+    }
+}
+
+// A problem found while compiling a program
+// The span is only known for the phases working on the source code
+#[wasm_bindgen]
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    phase: DiagnosticPhase,
+    severity: DiagnosticSeverity,
+    message: String,
+    span: Option<Span>,
+    // Stable identifier of the finding, such as `unused_variable`
+    code: Option<&'static str>,
+    // File of a multi-file project
+    file: Option<String>,
+}
+
+#[wasm_bindgen]
+impl Diagnostic {
+    pub fn phase(&self) -> DiagnosticPhase {
+        self.phase
+    }
+
+    pub fn phase_name(&self) -> String {
+        self.phase.as_str().to_owned()
+    }
+
+    pub fn severity(&self) -> DiagnosticSeverity {
+        self.severity
+    }
+
+    pub fn severity_name(&self) -> String {
+        self.severity.as_str().to_owned()
+    }
+
+    pub fn message(&self) -> String {
+        self.message.clone()
+    }
+
+    pub fn code(&self) -> Option<String> {
+        self.code.map(str::to_owned)
+    }
+
+    pub fn file(&self) -> Option<String> {
+        self.file.clone()
+    }
+
+    pub fn span(&self) -> Option<Span> {
+        self.span
+    }
+
+    pub fn has_location(&self) -> bool {
+        self.span.is_some()
+    }
+
+    pub fn start_line(&self) -> Option<usize> {
+        self.span.map(|s| s.start_line())
+    }
+
+    pub fn start_column(&self) -> Option<usize> {
+        self.span.map(|s| s.start_column())
+    }
+
+    pub fn end_line(&self) -> Option<usize> {
+        self.span.map(|s| s.end_line())
+    }
+
+    pub fn end_column(&self) -> Option<usize> {
+        self.span.map(|s| s.end_column())
+    }
+
+    pub fn start_offset(&self) -> Option<usize> {
+        self.span.map(|s| s.start_offset())
+    }
+
+    pub fn end_offset(&self) -> Option<usize> {
+        self.span.map(|s| s.end_offset())
+    }
+
+    // Allow JS to display it directly in template strings
+    #[wasm_bindgen(js_name = toString)]
+    pub fn js_to_string(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl Diagnostic {
+    pub fn new(phase: DiagnosticPhase, severity: DiagnosticSeverity, message: impl Into<String>, span: Option<Span>) -> Self {
+        Self {
+            phase,
+            severity,
+            message: message.into(),
+            span,
+            code: None,
+            file: None,
+        }
+    }
+
+    pub fn error(phase: DiagnosticPhase, message: impl Into<String>, span: Option<Span>) -> Self {
+        Self::new(phase, DiagnosticSeverity::Error, message, span)
+    }
+
+    pub fn with_span(mut self, span: Option<Span>) -> Self {
+        self.span = span;
+        self
+    }
+
+    pub fn with_code(mut self, code: &'static str) -> Self {
+        self.code = Some(code);
+        self
+    }
+
+    pub fn with_file(mut self, file: &str) -> Self {
+        self.file = Some(file.to_owned());
+        self
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == DiagnosticSeverity::Error
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.phase.as_str(), self.severity.as_str())?;
+        if let Some(code) = self.code {
+            write!(f, "[{}]", code)?;
+        }
+        if let Some(file) = &self.file {
+            write!(f, " in {}", file)?;
+        }
+
+        if let Some(span) = &self.span {
+            write!(f, " at line {}, column {}", span.start_line(), span.start_column())?;
+        }
+
+        write!(f, ": {}", self.message)
+    }
+}
@@ -0,0 +1,122 @@
+use std::collections::HashMap;
+
+use wasm_bindgen::prelude::wasm_bindgen;
+use xelis_bytecode::Module;
+
+use crate::listing::ChunkListing;
+
+// Disassembly of a single chunk annotated with the names known for it
+#[wasm_bindgen]
+#[derive(Debug, Clone)]
+pub struct ChunkDisassembly {
+    chunk_id: usize,
+    name: Option<String>,
+    is_entry: bool,
+    hook_id: Option<u8>,
+    instructions: Vec<String>,
+}
+
+#[wasm_bindgen]
+impl ChunkDisassembly {
+    pub fn chunk_id(&self) -> usize {
+        self.chunk_id
+    }
+
+    // Name of the Silex function, if known
+    pub fn name(&self) -> Option<String> {
+        self.name.clone()
+    }
+
+    pub fn is_entry(&self) -> bool {
+        self.is_entry
+    }
+
+    pub fn is_hook(&self) -> bool {
+        self.hook_id.is_some()
+    }
+
+    pub fn hook_id(&self) -> Option<u8> {
+        self.hook_id
+    }
+
+    pub fn kind(&self) -> String {
+        if self.is_entry {
+            "entry"
+        } else if self.hook_id.is_some() {
+            "hook"
+        } else {
+            "function"
+        }.to_owned()
+    }
+
+    pub fn instructions(&self) -> Vec<String> {
+        self.instructions.clone()
+    }
+
+    // Header followed by the instructions, one per line
+    pub fn text(&self) -> String {
+        let mut text = format!("; chunk {} - {}", self.chunk_id, self.kind());
+        if let Some(name) = &self.name {
+            text.push(' ');
+            text.push_str(name);
+        }
+
+        if let Some(hook_id) = self.hook_id {
+            text.push_str(&format!(" (hook {})", hook_id));
+        }
+
+        for instruction in &self.instructions {
+            text.push_str("\n    ");
+            text.push_str(instruction);
+        }
+
+        text
+    }
+}
+
+// Hook id of every chunk registered as a hook
+pub fn hooks_by_chunk(module: &Module) -> HashMap<usize, u8> {
+    (0..=u8::MAX)
+        .filter_map(|hook| module.get_chunk_id_of_hook(hook).map(|chunk| (chunk as usize, hook)))
+        .collect()
+}
+
+pub fn build(
+    module: &Module,
+    listing: &[ChunkListing],
+    chunk_names: &HashMap<usize, String>,
+    syscall_names: &HashMap<u16, String>,
+) -> Vec<ChunkDisassembly> {
+    let hooks = hooks_by_chunk(module);
+
+    listing
+        .iter()
+        .map(|chunk| {
+            let instructions = chunk.instructions
+                .iter()
+                .map(|instruction| {
+                    let target = if instruction.is_syscall() {
+                        instruction.operand(0).and_then(|id| syscall_names.get(&(id as u16)))
+                    } else if instruction.is_chunk_call() {
+                        instruction.operand(0).and_then(|id| chunk_names.get(&(id as usize)))
+                    } else {
+                        None
+                    };
+
+                    match target {
+                        Some(name) => format!("{} ; {}", instruction.text(), name),
+                        None => instruction.text(),
+                    }
+                })
+                .collect();
+
+            ChunkDisassembly {
+                chunk_id: chunk.id,
+                name: chunk_names.get(&chunk.id).cloned(),
+                is_entry: module.is_entry_chunk(chunk.id),
+                hook_id: hooks.get(&chunk.id).copied(),
+                instructions,
+            }
+        })
+        .collect()
+}
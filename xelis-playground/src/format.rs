@@ -0,0 +1,236 @@
+// Canonical formatting of Silex source code
+// The tokens read by the lexer are re-printed one by one so comments are kept in place:
+// four spaces indentation, braces on the line of their declaration,
+// single spaces around operators and at most one blank line in a row.
+// Commas are kept as written, the formatter never adds nor removes one.
+
+use xelis_lexer::Lexer;
+
+use crate::syntax::{self, SyntaxToken, TokenKind};
+
+const INDENT: &str = "    ";
+
+// Types written with parameters, such as `optional<u64>`
+const GENERIC_TYPES: &[&str] = &["optional", "map", "range"];
+
+struct Bracket<'a> {
+    text: &'a str,
+    // Content is written on its own lines
+    indented: bool,
+}
+
+fn is_open(text: &str) -> bool {
+    matches!(text, "(" | "[" | "{")
+}
+
+fn is_close(text: &str) -> bool {
+    matches!(text, ")" | "]" | "}")
+}
+
+// Whether an operator written after this token is a prefix operator
+fn starts_operand(previous: Option<&SyntaxToken>) -> bool {
+    match previous {
+        None => true,
+        Some(token) => match token.kind {
+            TokenKind::Operator => true,
+            TokenKind::Punctuation => !is_close(token.text),
+            TokenKind::Keyword => !matches!(token.text, "self" | "true" | "false" | "null"),
+            _ => false,
+        },
+    }
+}
+
+fn matching_brackets(tokens: &[SyntaxToken]) -> Vec<Option<usize>> {
+    let mut matching = vec![None; tokens.len()];
+    let mut stack = Vec::new();
+    for (i, token) in tokens.iter().enumerate() {
+        if token.kind != TokenKind::Punctuation {
+            continue;
+        }
+
+        if is_open(token.text) {
+            stack.push(i);
+        } else if is_close(token.text) {
+            if let Some(open) = stack.pop() {
+                matching[open] = Some(i);
+            }
+        }
+    }
+
+    matching
+}
+
+struct Formatter<'a> {
+    source: &'a str,
+    tokens: Vec<SyntaxToken<'a>>,
+    matching: Vec<Option<usize>>,
+    out: String,
+    brackets: Vec<Bracket<'a>>,
+    // Depth of the type parameters being written
+    generics: usize,
+    // Previous token was a prefix operator
+    prefix: bool,
+}
+
+impl<'a> Formatter<'a> {
+    fn newlines_before(&self, i: usize) -> usize {
+        match i.checked_sub(1) {
+            Some(previous) => self.source[self.tokens[previous].end..self.tokens[i].start]
+                .matches('\n')
+                .count(),
+            None => 0,
+        }
+    }
+
+    fn newline(&mut self, count: usize) {
+        let trimmed = self.out.trim_end_matches([' ', '\t']).len();
+        self.out.truncate(trimmed);
+        for _ in 0..count {
+            self.out.push('\n');
+        }
+
+        let depth = self.brackets.iter().filter(|b| b.indented).count();
+        self.out.push_str(&INDENT.repeat(depth));
+    }
+
+    fn space(&self, i: usize) -> bool {
+        let token = &self.tokens[i];
+        let Some(previous) = i.checked_sub(1).map(|p| &self.tokens[p]) else {
+            return false;
+        };
+
+        let (p, t) = (previous.text, token.text);
+        if token.kind == TokenKind::Comment {
+            return true;
+        }
+
+        if self.prefix {
+            return false;
+        }
+
+        match (p, t) {
+            (_, "," | ";" | ")" | "]") => false,
+            ("{", "}") => false,
+            (_, "}") | ("{", _) => true,
+            ("(" | "[", _) => false,
+            (_, "." | "::" | "..") | ("." | "::" | "..", _) => false,
+            (_, ":") => false,
+            (_, "<" | ">" | ">>") if self.generics > 0 || GENERIC_TYPES.contains(&p) => false,
+            ("<", _) if self.generics > 0 => false,
+            (_, "(" | "[") => !(previous.kind == TokenKind::Identifier || is_close(p) || p == ">" || p == "self"),
+            _ => true,
+        }
+    }
+
+    fn run(mut self) -> String {
+        for i in 0..self.tokens.len() {
+            let token = self.tokens[i];
+            let previous = i.checked_sub(1).map(|p| self.tokens[p]);
+            let after_comment = previous.map(|p| p.kind == TokenKind::Comment).unwrap_or(false);
+            let mut newlines = self.newlines_before(i).min(2);
+
+            // Braces stay on the line of their declaration, unless they start a block statement
+            let starts_statement = previous.map(|p| matches!(p.text, ";" | "{" | "}")).unwrap_or(true);
+            if !after_comment && (token.text == "," || token.text == ";" || (token.text == "{" && !starts_statement)) {
+                newlines = 0;
+            }
+
+            if token.text == "else" && previous.map(|p| p.text == "}").unwrap_or(false) {
+                newlines = 0;
+            }
+
+            if previous.map(|p| is_open(p.text)).unwrap_or(false) {
+                newlines = newlines.min(1);
+                // Content of a block is always on its own lines
+                if self.brackets.last().map(|b| b.indented && b.text == "{").unwrap_or(false) {
+                    newlines = newlines.max(1);
+                }
+            }
+
+            if token.kind == TokenKind::Punctuation && is_close(token.text) {
+                newlines = newlines.min(1);
+                if self.brackets.pop().map(|b| b.indented).unwrap_or(false) {
+                    newlines = newlines.max(1);
+                }
+            }
+
+            if newlines > 0 {
+                self.newline(newlines);
+            } else if self.space(i) {
+                self.out.push(' ');
+            }
+
+            self.prefix = matches!(token.text, "-" | "!") && starts_operand(previous.as_ref());
+
+            match token.text {
+                "<" if GENERIC_TYPES.contains(&previous.map(|p| p.text).unwrap_or("")) || self.generics > 0 => self.generics += 1,
+                ">" if self.generics > 0 => self.generics -= 1,
+                ">>" if self.generics > 0 => self.generics = self.generics.saturating_sub(2),
+                _ => {}
+            }
+
+            if token.kind == TokenKind::Comment {
+                self.out.push_str(token.text.trim_end());
+            } else {
+                self.out.push_str(token.text);
+            }
+
+            if token.kind == TokenKind::Punctuation && is_open(token.text) {
+                let indented = match (token.text, self.matching[i]) {
+                    // A block written on several lines is indented
+                    ("{", Some(close)) => self.source[token.end..self.tokens[close].start].contains('\n'),
+                    _ => i + 1 < self.tokens.len() && self.newlines_before(i + 1) > 0,
+                };
+
+                self.brackets.push(Bracket {
+                    text: token.text,
+                    indented,
+                });
+            }
+        }
+
+        let mut out = self.out.trim_end().to_owned();
+        if !out.is_empty() {
+            out.push('\n');
+        }
+        out
+    }
+}
+
+// Tokens of the lexer with the comments, in the shape expected by the formatter
+fn read_tokens(source: &str) -> Result<Vec<SyntaxToken<'_>>, String> {
+    syntax::scan(source)
+        .into_iter()
+        .map(|token| match token.kind {
+            TokenKind::Unknown => Err(format!("Invalid token `{}`", token.text)),
+            // Builtin types are laid out like the names of user types
+            TokenKind::Type => Ok(SyntaxToken {
+                kind: TokenKind::Identifier,
+                ..token
+            }),
+            _ => Ok(token),
+        })
+        .collect()
+}
+
+pub fn format(source: &str) -> Result<String, String> {
+    let tokens = read_tokens(source)?;
+    Ok(Formatter {
+        source,
+        matching: matching_brackets(&tokens),
+        tokens,
+        out: String::new(),
+        brackets: Vec::new(),
+        generics: 0,
+        prefix: false,
+    }.run())
+}
+
+// Tokens read by the lexer, to check that the formatting kept them
+pub fn lexer_tokens(source: &str) -> Result<Vec<String>, String> {
+    Lexer::new(source)
+        .map(|token| token
+            .map(|token| format!("{:?}", token.token))
+            .map_err(|err| format!("{:#}", err)))
+        .collect()
+}
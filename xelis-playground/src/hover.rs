@@ -0,0 +1,200 @@
+// Information shown when hovering an identifier or an expression
+// Types are the ones resolved by the parser, on what's left of the code
+// once the declarations failing to parse are removed.
+
+use wasm_bindgen::{prelude::wasm_bindgen, JsValue};
+use xelis_types::Type;
+
+use crate::{
+    abi::parse_type_name,
+    span::{LineIndex, Span},
+    symbols::{SymbolIndex, SymbolKind, Target},
+    Func,
+};
+
+#[wasm_bindgen]
+#[derive(Debug, Clone)]
+pub struct Hover {
+    name: String,
+    kind: String,
+    // Type of the value, or the return type of a function
+    type_name: Option<String>,
+    ty: Option<Type>,
+    signature: Option<String>,
+    doc: Option<String>,
+    // Value of a constant as written in the source
+    value: Option<String>,
+    syscall_id: Option<u16>,
+    cost: Option<u64>,
+    span: Span,
+}
+
+#[wasm_bindgen]
+impl Hover {
+    pub fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    pub fn kind(&self) -> String {
+        self.kind.clone()
+    }
+
+    pub fn type_name(&self) -> Option<String> {
+        self.type_name.clone()
+    }
+
+    // Type serialized as JSON, undefined if it couldn't be resolved
+    pub fn type_json(&self) -> JsValue {
+        self.ty
+            .as_ref()
+            .map(|ty| serde_wasm_bindgen::to_value(ty).expect("Expected valid serialization"))
+            .unwrap_or(JsValue::UNDEFINED)
+    }
+
+    pub fn signature(&self) -> Option<String> {
+        self.signature.clone()
+    }
+
+    pub fn doc(&self) -> Option<String> {
+        self.doc.clone()
+    }
+
+    pub fn value(&self) -> Option<String> {
+        self.value.clone()
+    }
+
+    pub fn syscall_id(&self) -> Option<u16> {
+        self.syscall_id
+    }
+
+    pub fn cost(&self) -> Option<u64> {
+        self.cost
+    }
+
+    // Range of the hovered identifier
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
+    // Content ready to be displayed by an editor
+    pub fn markdown(&self) -> String {
+        let code = self.signature
+            .clone()
+            .unwrap_or_else(|| match &self.type_name {
+                Some(ty) => format!("{}: {}", self.name, ty),
+                None => self.name.clone(),
+            });
+
+        let mut markdown = format!("```silex\n{}\n```", code);
+        if let Some(value) = &self.value {
+            markdown.push_str(&format!("\n\nValue: `{}`", value));
+        }
+
+        if let Some(doc) = &self.doc {
+            markdown.push_str("\n\n");
+            markdown.push_str(doc);
+        }
+
+        if let (Some(id), Some(cost)) = (self.syscall_id, self.cost) {
+            markdown.push_str(&format!("\n\nSyscall {}, cost: {}", id, cost));
+        }
+
+        markdown
+    }
+}
+
+// Value of the expression under the cursor
+fn expression(index: &SymbolIndex, lines: &LineIndex, code: &str, offset: usize) -> Option<Hover> {
+    let (start, end, ty) = index.expression_at(offset)?;
+    Some(Hover {
+        name: code.get(*start..*end)?.to_owned(),
+        kind: "expression".to_owned(),
+        type_name: Some(ty.to_string()),
+        ty: Some(ty.clone()),
+        signature: None,
+        doc: None,
+        value: None,
+        syscall_id: None,
+        cost: None,
+        span: lines.span(*start, *end),
+    })
+}
+
+// Named types are resolved with `opaque` for the ones of the environment
+pub fn hover(
+    index: &SymbolIndex,
+    lines: &LineIndex,
+    code: &str,
+    offset: usize,
+    env_function: impl Fn(&Target) -> Option<Func>,
+    opaque: &dyn Fn(&str) -> Option<Type>,
+) -> Option<Hover> {
+    let Some(occurrence) = index.occurrence_at(offset).filter(|o| o.target != Target::Unknown) else {
+        return expression(index, lines, code, offset);
+    };
+    let span = lines.span(occurrence.start, occurrence.end);
+
+    let hover = match &occurrence.target {
+        Target::Symbol(id) => {
+            let symbol = &index.symbols[*id];
+            let signature = match symbol.kind {
+                SymbolKind::Parameter | SymbolKind::Local | SymbolKind::Field => None,
+                _ => Some(index.signature(*id)),
+            };
+
+            let ty = match symbol.kind {
+                SymbolKind::Variant => symbol.parent.and_then(|p| index.symbols[p].resolved.clone()),
+                _ => symbol.resolved.clone(),
+            };
+            let type_name = match symbol.kind {
+                SymbolKind::Struct | SymbolKind::Enum => Some(symbol.name.clone()),
+                SymbolKind::Variant => symbol.parent.map(|p| index.symbols[p].name.clone()),
+                _ => ty.as_ref().map(Type::to_string).or_else(|| symbol.ty.clone()),
+            };
+
+            Hover {
+                name: symbol.name.clone(),
+                kind: symbol.kind.as_str().to_owned(),
+                type_name,
+                ty,
+                signature,
+                doc: symbol.doc.clone(),
+                value: symbol.value.clone(),
+                syscall_id: None,
+                cost: None,
+                span,
+            }
+        }
+        target @ (Target::Function { name, .. } | Target::Method { name, .. }) => {
+            let function = env_function(target)?;
+            let type_name = function.return_type();
+            Hover {
+                name: name.clone(),
+                kind: "builtin".to_owned(),
+                ty: type_name.as_deref().and_then(|name| parse_type_name(name, opaque)),
+                type_name,
+                signature: Some(function.signature()),
+                doc: function.comment(),
+                value: None,
+                syscall_id: Some(function.syscall_id()),
+                cost: Some(function.cost()),
+                span,
+            }
+        }
+        Target::Type(name) => Hover {
+            name: name.clone(),
+            kind: "type".to_owned(),
+            type_name: Some(name.clone()),
+            ty: parse_type_name(name, opaque),
+            signature: None,
+            doc: None,
+            value: None,
+            syscall_id: None,
+            cost: None,
+            span,
+        },
+        Target::Unknown => return None,
+    };
+
+    Some(hover)
+}
@@ -1,7 +1,31 @@
+mod abi;
+mod builtins;
+mod completion;
+mod deploy;
+mod diagnostic;
+mod disassembly;
+mod format;
+mod hover;
+mod listing;
+mod navigation;
+mod outline;
+mod project;
+mod rename;
+mod semantic;
+mod signature_help;
+mod source_map;
+mod span;
+mod stats;
 mod storage;
+mod symbols;
+mod syntax;
+mod tokens;
+mod verify;
+mod versions;
+mod warnings;
 
 use std::{borrow::Cow, collections::HashMap, sync::{
-    atomic::{AtomicBool, Ordering}, mpsc, Arc, Mutex
+    atomic::{AtomicBool, Ordering}, mpsc, Arc
 }};
 
 use cfg_if::cfg_if;
@@ -9,6 +33,9 @@ use human_bytes::human_bytes;
 use humantime::format_duration;
 use indexmap::IndexMap;
 use storage::MockStorage;
+use builtins::Environment;
+use span::LineIndex;
+use symbols::{Parsed, SymbolIndex, Target};
 #[cfg(all(
     target_arch = "wasm32",
     target_vendor = "unknown",
@@ -17,14 +44,14 @@ use storage::MockStorage;
 use tokio_with_wasm as tokio;
 
 use wasm_bindgen::{prelude::wasm_bindgen, JsValue};
-use xelis_assembler::Disassembler;
+use xelis_assembler::{Assembler, Disassembler};
 use xelis_builder::{Builder, EnvironmentBuilder};
 use xelis_bytecode::Module;
 use xelis_common::{
     account::CiphertextCache,
     asset::{AssetData, AssetOwner, MaxSupplyMode},
     block::{Block, BlockHeader, BlockVersion},
-    config::{COIN_VALUE, MAXIMUM_SUPPLY, XELIS_ASSET},
+    config::{COIN_VALUE, MAXIMUM_SUPPLY, MAX_GAS_USAGE_PER_TX, XELIS_ASSET},
     context::NoOpBuildHasher,
     contract::{
         ChainState,
@@ -48,6 +75,8 @@ use xelis_common::{
     serializer::Serializer,
     transaction::{
         ContractDeposit,
+        DeployContractPayload,
+        InvokeConstructorPayload,
         InvokeContractPayload,
         Reference,
         Transaction,
@@ -55,12 +84,30 @@ use xelis_common::{
         TxVersion
     }, utils::format_xelis
 };
+use xelis_ast::Program as AstProgram;
 use xelis_compiler::Compiler;
 use xelis_lexer::Lexer;
-use xelis_parser::Parser;
+use xelis_parser::{mapper::GlobalMapper, Parser};
 use xelis_types::Type;
-use xelis_vm::{FnInstance, FnParams, FnReturnType, FunctionHandler, Primitive, SysCallResult, VM, VMContext, ValueCell};
-use serde::Deserialize;
+use xelis_vm::{tid, FnInstance, FnParams, FnReturnType, FunctionHandler, Primitive, SysCallResult, VM, VMContext, ValueCell};
+use serde::{Deserialize, Serialize};
+
+pub use completion::CompletionItem;
+pub use deploy::DeployPayload;
+pub use diagnostic::{Diagnostic, DiagnosticPhase, DiagnosticSeverity};
+pub use disassembly::ChunkDisassembly;
+pub use hover::Hover;
+pub use navigation::Definition;
+pub use outline::OutlineItem;
+pub use rename::TextEdit;
+pub use semantic::SemanticToken;
+pub use signature_help::SignatureHelp;
+pub use source_map::SourceMapEntry;
+pub use span::Span;
+pub use stats::{ChunkStats, ModuleStats, OpcodeCount};
+pub use tokens::SourceToken;
+pub use verify::{ChunkAnalysis, VerifyFinding, VerifyReport};
+pub use versions::VersionReport;
 
 #[wasm_bindgen]
 extern "C" {
@@ -74,7 +121,8 @@ macro_rules! log {
             if #[cfg(target_arch = "wasm32")] {
                 console_log(&format!($($t)*));
             } else {
-                println!($($t)*);
+                // stdout is kept for the output of the native tools
+                eprintln!($($t)*);
             }
         }
     }};
@@ -83,20 +131,65 @@ macro_rules! log {
 #[wasm_bindgen]
 pub struct Silex {
     environments: HashMap<ContractVersion, EnvironmentBuilder<'static, ContractMetadata>>,
+    logs_sender: mpsc::Sender<String>,
     logs_receiver: mpsc::Receiver<String>,
     is_running: AtomicBool,
     selected_version: ContractVersion,
 }
 
 #[wasm_bindgen]
+#[derive(Clone)]
 pub struct Program {
     module: Module,
     entries: Vec<Entry>,
-    abi: String,
+    // None if the module was loaded without ABI or its generation failed
+    abi: Option<String>,
+    warnings: Vec<Diagnostic>,
+    source_map: Vec<SourceMapEntry>,
+    // Silex function of each chunk, empty if not compiled from source
+    functions: Vec<FunctionInfo>,
+    // Environment functions by syscall id, unknown if not compiled from source
+    syscalls: Option<HashMap<u16, Func>>,
+}
+
+// Declaration of a chunk as written in the source
+#[derive(Debug, Clone)]
+struct FunctionInfo {
+    name: String,
+    parameters: Vec<Parameter>,
+    return_type: Option<Type>,
+    doc: Option<String>,
 }
 
 #[wasm_bindgen]
 impl Program {
+    // Load a module serialized with `to_bytes`
+    // The ABI, if provided, is used to recover the entries and their parameters,
+    // its opaque types are the ones of the contract version (V1 by default)
+    pub fn from_bytes(bytes: &[u8], abi: Option<String>, contract_version: Option<u8>) -> Result<Program, JsValue> {
+        let module = Module::from_bytes(bytes)
+            .map_err(|e| JsValue::from_str(&format!("Invalid module bytes: {}", e)))?;
+
+        Self::from_module(module, abi, Self::contract_version(contract_version)?)
+    }
+
+    // Load a module serialized with `to_hex`
+    pub fn from_hex(hex: &str, abi: Option<String>, contract_version: Option<u8>) -> Result<Program, JsValue> {
+        let hex = hex.trim();
+        let module = Module::from_hex(hex.strip_prefix("0x").unwrap_or(hex))
+            .map_err(|e| JsValue::from_str(&format!("Invalid module hex: {}", e)))?;
+
+        Self::from_module(module, abi, Self::contract_version(contract_version)?)
+    }
+
+    // Load a module serialized with `to_json`
+    pub fn from_json(json: &str, abi: Option<String>, contract_version: Option<u8>) -> Result<Program, JsValue> {
+        let module: Module = serde_json::from_str(json)
+            .map_err(|e| JsValue::from_str(&format!("Invalid module JSON: {}", e)))?;
+
+        Self::from_module(module, abi, Self::contract_version(contract_version)?)
+    }
+
     // Get the entries of the program
     pub fn entries(&self) -> Vec<Entry> {
         self.entries.clone()
@@ -115,8 +208,21 @@ impl Program {
             .expect("Failed to serialize module to JSON")
     }
 
+    // ABI as JSON text, empty if no ABI is available
+    // Deprecated: `abi_json` tells apart a missing ABI
     pub fn to_abi(&self) -> String {
-        self.abi.clone()
+        self.abi.clone().unwrap_or_default()
+    }
+
+    // ABI as a plain JS object: entries, parameter types, events and types
+    // null if no ABI is available
+    pub fn abi_json(&self) -> Result<JsValue, JsValue> {
+        let Some(abi) = self.abi_value().map_err(|e| JsValue::from_str(&e))? else {
+            return Ok(JsValue::NULL);
+        };
+
+        abi.serialize(&serde_wasm_bindgen::Serializer::json_compatible())
+            .map_err(|e| JsValue::from_str(&format!("Failed to convert the ABI: {}", e)))
     }
 
     pub fn to_asm(&self) -> String {
@@ -126,10 +232,241 @@ impl Program {
             .to_string()
     }
 
+    // Size report of the compiled module
+    pub fn stats(&self) -> Result<ModuleStats, JsValue> {
+        let listing = self.listing()
+            .map_err(|e| JsValue::from_str(&e))?;
+
+        Ok(ModuleStats::new(&self.module, &listing, self.to_bytes().len()))
+    }
+
+    // Non-fatal findings of the compilation
+    pub fn warnings(&self) -> Vec<Diagnostic> {
+        self.warnings.clone()
+    }
+
+    // Call sites of the syscalls and chunk calls of each chunk
+    // Empty if the program was not compiled from source
+    pub fn source_map(&self) -> Vec<SourceMapEntry> {
+        self.source_map.clone()
+    }
+
+    // Call site of a call instruction of a chunk
+    pub fn source_location(&self, chunk_id: usize, instruction: usize) -> Option<Span> {
+        self.source_map
+            .iter()
+            .find(|entry| entry.contains(chunk_id, instruction))
+            .map(SourceMapEntry::span)
+    }
+
+    // Payload of the transaction deploying the program with the contract version
+    // The constructor, if any, is invoked with the deposits (asset hash to amount) and the max gas.
+    // It can't be given arguments: the deploy transaction has no field for them.
+    // The contract hash is the hash of the signed transaction, which also depends on the wallet
+    // (fee, nonce, signature), so it is not part of the payload: see `DeployPayload::contract_hash`
+    pub fn deploy_payload(&self, version: u8, constructor_max_gas: Option<u64>, deposits: JsValue) -> Result<DeployPayload, JsValue> {
+        let deposits: HashMap<String, String> = if deposits.is_null() || deposits.is_undefined() {
+            HashMap::new()
+        } else {
+            serde_wasm_bindgen::from_value(deposits)
+                .map_err(|e| JsValue::from_str(&format!("Failed to parse deposits: {:?}", e)))?
+        };
+
+        self.build_deploy_payload(version, constructor_max_gas, deposits)
+            .map_err(|err| JsValue::from_str(&err))
+    }
+
+    // Static checks of the module: unreachable chunks, stack depths, jumps, syscalls and recursions
+    // Syscalls are checked against the environment the program was compiled for
+    pub fn verify(&self) -> VerifyReport {
+        verify::verify(&self.module, &listing::decode(&self.module), &self.chunk_names(), self.syscalls.as_ref())
+    }
+
     // Check if the program has a constructor (hook id 0)
     pub fn has_constructor(&self) -> bool {
         self.module.get_chunk_id_of_hook(0).is_some()
     }
+
+    // Every hook implemented by the module
+    // Names and parameters are only known if the program was compiled from source
+    pub fn hooks(&self) -> Vec<Hook> {
+        (0..=u8::MAX)
+            .filter_map(|id| {
+                let chunk_id = self.module.get_chunk_id_of_hook(id)? as usize;
+                let hook = match self.functions.get(chunk_id) {
+                    Some(function) => Hook {
+                        id,
+                        chunk_id: chunk_id as u16,
+                        name: function.name.clone(),
+                        parameters: function.parameters.clone(),
+                        doc: function.doc.clone(),
+                    },
+                    None => Hook {
+                        id,
+                        chunk_id: chunk_id as u16,
+                        name: if id == 0 { "constructor".to_owned() } else { format!("hook_{}", id) },
+                        parameters: Vec::new(),
+                        doc: None,
+                    },
+                };
+
+                Some(hook)
+            })
+            .collect()
+    }
+}
+
+impl Program {
+    // ABI as JSON text, None if no ABI is available
+    pub fn abi_text(&self) -> Option<String> {
+        self.abi.clone()
+    }
+
+    // Payload of the transaction deploying the program, errors are not converted to JS values
+    pub fn build_deploy_payload(&self, version: u8, constructor_max_gas: Option<u64>, deposits: HashMap<String, String>) -> Result<DeployPayload, String> {
+        let contract_version = ContractVersion::from_bytes(&[version])
+            .map_err(|_| "Invalid contract version".to_owned())?;
+        let deposits = Silex::parse_deposits(deposits)?;
+
+        let constructor = self.module
+            .get_chunk_id_of_hook(0)
+            .and_then(|chunk_id| self.functions.get(chunk_id as usize));
+        if constructor.is_some_and(|function| !function.parameters.is_empty()) {
+            return Err("The constructor can't take parameters, the deploy transaction doesn't carry arguments".to_owned());
+        }
+
+        let invoke = if self.has_constructor() {
+            Some(InvokeConstructorPayload {
+                max_gas: constructor_max_gas.unwrap_or(MAX_GAS_USAGE_PER_TX),
+                deposits,
+            })
+        } else if !deposits.is_empty() || constructor_max_gas.is_some() {
+            return Err("Deposits and max gas are only used by a constructor".to_owned());
+        } else {
+            None
+        };
+
+        let has_constructor = invoke.is_some();
+        let data = TransactionType::DeployContract(DeployContractPayload {
+            contract_version,
+            module: self.module.clone().into(),
+            invoke,
+        });
+
+        DeployPayload::new(version, &data, has_constructor)
+    }
+
+    fn abi_value(&self) -> Result<Option<serde_json::Value>, String> {
+        self.abi
+            .as_deref()
+            .map(|abi| serde_json::from_str(abi).map_err(|e| format!("Invalid ABI JSON: {}", e)))
+            .transpose()
+    }
+
+    // Instructions of each chunk decoded from the bytecode
+    fn listing(&self) -> Result<Vec<listing::ChunkListing>, String> {
+        let listing = listing::decode(&self.module);
+        match listing.iter().find_map(|chunk| chunk.error.as_ref().map(|error| (chunk.id, error))) {
+            Some((chunk_id, error)) => Err(format!("Invalid chunk {} at offset {}: {}", chunk_id, error.offset, error.message)),
+            None => Ok(listing),
+        }
+    }
+
+    // Name of each chunk, falling back on the entries names when the source is unknown
+    fn chunk_names(&self) -> HashMap<usize, String> {
+        if !self.functions.is_empty() {
+            return self.functions
+                .iter()
+                .map(|function| function.name.clone())
+                .enumerate()
+                .collect();
+        }
+
+        self.entries
+            .iter()
+            .map(|entry| (entry.chunk_id as usize, entry.name.clone()))
+            .collect()
+    }
+
+    fn contract_version(version: Option<u8>) -> Result<ContractVersion, JsValue> {
+        match version {
+            Some(version) => ContractVersion::from_bytes(&[version])
+                .map_err(|_| JsValue::from_str("Invalid contract version")),
+            None => Ok(ContractVersion::V1),
+        }
+    }
+
+    fn from_module(module: Module, abi: Option<String>, version: ContractVersion) -> Result<Program, JsValue> {
+        let entries = match abi.as_deref() {
+            Some(abi) => {
+                let environment = build_environment::<MockStorage>(version);
+                let opaque = |name: &str| environment.get_opaque_by_name(name).cloned().map(Type::Opaque);
+                abi::entries_from_abi(abi, &module, &opaque)
+                    .map_err(|e| JsValue::from_str(&e))?
+            }
+            // Without ABI, only the public chunks are known: their parameters are not
+            // kept by the module so they can't be executed
+            None => (0..module.chunks().len())
+                .filter(|id| module.is_entry_chunk(*id))
+                .enumerate()
+                .map(|(id, chunk_id)| Entry {
+                    id,
+                    chunk_id: chunk_id as u16,
+                    name: format!("chunk_{}", chunk_id),
+                    parameters: Vec::new(),
+                    // Entries always return their exit code
+                    return_type: "u64".to_owned(),
+                    doc: None,
+                    executable: false,
+                })
+                .collect(),
+        };
+
+        if let Some(entry) = entries.iter().find(|e| !module.is_entry_chunk(e.chunk_id as usize)) {
+            return Err(JsValue::from_str(&format!("ABI entry `{}` does not match a public chunk of the module", entry.name)));
+        }
+
+        log!("Loaded module with {} entry points", entries.len());
+        Ok(Program {
+            module,
+            entries,
+            abi,
+            warnings: Vec::new(),
+            source_map: Vec::new(),
+            functions: Vec::new(),
+            syscalls: None,
+        })
+    }
+}
+
+// Result of a compilation reporting every problem found
+#[wasm_bindgen]
+pub struct CompileResult {
+    program: Option<Program>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+#[wasm_bindgen]
+impl CompileResult {
+    // The program is only available if no error was found
+    pub fn program(&self) -> Option<Program> {
+        self.program.clone()
+    }
+
+    pub fn is_success(&self) -> bool {
+        self.program.is_some()
+    }
+
+    pub fn diagnostics(&self) -> Vec<Diagnostic> {
+        self.diagnostics.clone()
+    }
+
+    pub fn error_count(&self) -> usize {
+        self.diagnostics
+            .iter()
+            .filter(|d| d.is_error())
+            .count()
+    }
 }
 
 #[wasm_bindgen]
@@ -162,6 +499,10 @@ pub struct Entry {
     chunk_id: u16,
     name: String,
     parameters: Vec<Parameter>,
+    return_type: String,
+    doc: Option<String>,
+    // Parameters are known
+    executable: bool,
 }
 
 #[wasm_bindgen]
@@ -170,6 +511,54 @@ impl Entry {
         self.id
     }
 
+    pub fn chunk_id(&self) -> u16 {
+        self.chunk_id
+    }
+
+    pub fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    pub fn parameters(&self) -> Vec<Parameter> {
+        self.parameters.clone()
+    }
+
+    // Entries return their exit code
+    pub fn return_type(&self) -> String {
+        self.return_type.clone()
+    }
+
+    pub fn doc(&self) -> Option<String> {
+        self.doc.clone()
+    }
+
+    // Entries of a module loaded without its ABI can't be executed
+    pub fn is_executable(&self) -> bool {
+        self.executable
+    }
+}
+
+// Function called by the chain on an event, such as the constructor on deploy
+#[wasm_bindgen]
+#[derive(Debug, Clone)]
+pub struct Hook {
+    id: u8,
+    chunk_id: u16,
+    name: String,
+    parameters: Vec<Parameter>,
+    doc: Option<String>,
+}
+
+#[wasm_bindgen]
+impl Hook {
+    pub fn id(&self) -> u8 {
+        self.id
+    }
+
+    pub fn chunk_id(&self) -> u16 {
+        self.chunk_id
+    }
+
     pub fn name(&self) -> String {
         self.name.clone()
     }
@@ -177,6 +566,10 @@ impl Entry {
     pub fn parameters(&self) -> Vec<Parameter> {
         self.parameters.clone()
     }
+
+    pub fn doc(&self) -> Option<String> {
+        self.doc.clone()
+    }
 }
 
 #[wasm_bindgen]
@@ -302,9 +695,42 @@ impl ExecutionResult {
 
         event_entries
     }
+
+    // Whole result serialized as JSON
+    pub fn to_json(&self) -> String {
+        let storage: Vec<_> = self.storage()
+            .into_iter()
+            .map(|entry| serde_json::json!({
+                "contract": entry.contract,
+                "key": entry.key,
+                "value": entry.value,
+            }))
+            .collect();
+
+        let events: Vec<_> = self.events()
+            .into_iter()
+            .map(|entry| serde_json::json!({
+                "contract": entry.contract,
+                "event_id": entry.event_id,
+                "event": entry.event,
+            }))
+            .collect();
+
+        serde_json::json!({
+            "value": self.value(),
+            "is_error": self.is_error(),
+            "logs": self.logs,
+            "elapsed_time": self.elapsed_time,
+            "used_gas": self.used_gas,
+            "used_memory": self.used_memory,
+            "storage": storage,
+            "events": events,
+        }).to_string()
+    }
 }
 
 #[wasm_bindgen]
+#[derive(Clone)]
 pub struct Func {
     name: String,
     on_type: Option<String>,
@@ -342,6 +768,10 @@ impl Func {
         self.syscall_id
     }
 
+    pub fn cost(&self) -> u64 {
+        self.cost
+    }
+
     pub fn gas_cost_formatted(&self) -> String {
         format_xelis(self.cost)
     }
@@ -349,6 +779,23 @@ impl Func {
     pub fn comment(&self) -> Option<String> {
         self.comment.clone()
     }
+
+    // Declaration written with the Silex syntax
+    pub fn signature(&self) -> String {
+        let name = match (&self.on_type, self.on_instance) {
+            (Some(ty), true) => format!("(self {}) {}", ty, self.name),
+            (Some(ty), false) => format!("{}::{}", ty, self.name),
+            (None, _) => self.name.clone(),
+        };
+
+        let mut signature = format!("fn {}({})", name, self.params.join(", "));
+        if let Some(return_type) = &self.return_type {
+            signature.push_str(" -> ");
+            signature.push_str(return_type);
+        }
+
+        signature
+    }
 }
 
 #[wasm_bindgen]
@@ -378,7 +825,36 @@ impl ConstFunc {
     }
 }
 
-static LOGS_SENDER: Mutex<Option<mpsc::Sender<String>>> = Mutex::new(None);
+// Output of the contracts run by a Silex instance, given to the VM in its context
+struct LogSender(mpsc::Sender<String>);
+
+tid!(LogSender);
+
+// Maximum number of errors reported by a recovering compilation
+const MAX_RECOVERED_ERRORS: usize = 50;
+
+// Outcome of removing the code responsible of an error
+enum Recovery {
+    Failed,
+    Blanked,
+    // Only the body of the function was removed
+    Stubbed(String),
+    // The whole declaration of the function was removed
+    Removed(String),
+}
+
+// Code left once the errors are removed by a recovering parse
+struct Recovered {
+    // Same offsets as the original code, the removed code is replaced by spaces
+    source: String,
+    diagnostics: Vec<Diagnostic>,
+    // Whether the code left is accepted by the parser
+    parsed: bool,
+}
+
+fn is_blank(code: &str) -> bool {
+    code.chars().all(char::is_whitespace)
+}
 
 #[wasm_bindgen]
 #[derive(Deserialize, Debug, Clone)]
@@ -416,11 +892,9 @@ impl Silex {
     #[wasm_bindgen(constructor)]
     pub fn new() -> Self {
         log!("Initializing Silex...");
-        // Patch the environment to include a println function that sends logs to the receiver
+        // The println function of the environment sends the logs to the receiver
         let (sender, receiver) = mpsc::channel();
 
-        *LOGS_SENDER.lock().unwrap() = Some(sender);
-
         log!("Setting up environment...");
 
         Self {
@@ -428,173 +902,647 @@ impl Silex {
                 .into_iter()
                 .map(|version| (version, Self::create_environment(version)))
                 .collect(),
+            logs_sender: sender,
             logs_receiver: receiver,
             is_running: AtomicBool::new(false),
             selected_version: ContractVersion::V1,
         }
     }
 
-    fn println_fn(_: FnInstance, params: FnParams, _: &ModuleMetadata, _: &mut VMContext) -> FnReturnType<ContractMetadata> {
-        let param = &params[0];
-        cfg_if! {
-            if #[cfg(target_arch = "wasm32")] {
-                let lock = LOGS_SENDER.lock().unwrap();
-                let sender = lock.as_ref().unwrap();
-                sender
-                    .send(format!("{}", param.as_ref()))
-                    .unwrap();
-            } else {
-                println!("{}", param.as_ref());
+    // Output of the contract is collected in the logs of the execution,
+    // stdout is kept for the results of the native tools
+    fn send_log(context: &VMContext, message: String) {
+        match context.get::<LogSender>() {
+            Some(sender) => {
+                // The receiver is gone, don't lose the output
+                if let Err(err) = sender.0.send(message) {
+                    log!("{}", err.0);
+                }
             }
+            None => log!("{}", message),
         }
+    }
 
+    fn println_fn(_: FnInstance, params: FnParams, _: &ModuleMetadata, context: &mut VMContext) -> FnReturnType<ContractMetadata> {
+        Self::send_log(context, format!("{}", params[0].as_ref()));
         Ok(SysCallResult::None)
     }
 
-    fn debug_fn(_: FnInstance, params: FnParams, _: &ModuleMetadata, _: &mut VMContext) -> FnReturnType<ContractMetadata> {
-        let param = &params[0];
-        cfg_if! {
-            if #[cfg(target_arch = "wasm32")] {
-                let lock = LOGS_SENDER.lock().unwrap();
-                let sender = lock.as_ref().unwrap();
-                sender
-                    .send(format!("{:?}", param.as_ref()))
-                    .unwrap();
-            } else {
-                println!("DEBUG: {}", param.as_ref());
+    fn debug_fn(_: FnInstance, params: FnParams, _: &ModuleMetadata, context: &mut VMContext) -> FnReturnType<ContractMetadata> {
+        Self::send_log(context, format!("{:?}", params[0].as_ref()));
+        Ok(SysCallResult::None)
+    }
+
+    fn compile_internal(&self, code: &str) -> Result<Program, Diagnostic> {
+        self.compile_for_version(code, self.selected_version)
+    }
+
+    // Run the lexer and the parser on the code, nothing is compiled
+    fn parse<'a>(&'a self, code: &'a str, version: ContractVersion) -> Result<(AstProgram, GlobalMapper<'a>), Diagnostic> {
+        let index = LineIndex::new(code);
+
+        let mut tokens = Vec::new();
+        for token in Lexer::new(code) {
+            match token {
+                Ok(token) => tokens.push(token),
+                Err(err) => {
+                    log!("Lexer error: {:#}", err);
+                    // The lexer stops right after the last token it was able to read,
+                    // the error is on the first invalid character following it
+                    let start = tokens
+                        .last()
+                        .map(|token| index.offset(token.line, token.column_end))
+                        .unwrap_or(0);
+                    let span = syntax::scan(code)
+                        .into_iter()
+                        .find(|token| token.kind == syntax::TokenKind::Unknown && token.start >= start)
+                        .map(|token| index.span(token.start, token.end))
+                        .unwrap_or_else(|| index.span_to_line_end(start));
+
+                    return Err(Diagnostic::error(
+                        DiagnosticPhase::Lex,
+                        format!("{:#}", err),
+                        Some(span),
+                    ));
+                }
             }
         }
 
-        Ok(SysCallResult::None)
+        let environment = &self.environments[&version];
+        Parser::with(tokens.into_iter(), environment)
+            .parse()
+            .map_err(|err| {
+                log!("Parser error: {:#}", err);
+                Diagnostic::error(
+                    DiagnosticPhase::Parse,
+                    format!("{:#}", err.kind),
+                    Some(index.columns_span(err.line, err.column_start, err.column_end)),
+                )
+            })
     }
 
-    fn compile_internal(&self, code: &str) -> anyhow::Result<Program> {
-        log!("Compiling code:\n{}", code);
-        let tokens = Lexer::new(code)
-            .into_iter()
-            .collect::<Result<Vec<_>, _>>()?;
+    fn compile_for_version(&self, code: &str, version: ContractVersion) -> Result<Program, Diagnostic> {
+        log!("Compiling code for version {:?}", version);
+        let index = LineIndex::new(code);
+        let (program, mapper) = self.parse(code, version)?;
+        let environment = &self.environments[&version];
 
-        let environment = &self.environments[&self.selected_version];
-        let parser = Parser::with(tokens.into_iter(), &environment);
-        let (program, mapper) = match parser.parse() {
-            Ok(res) => res,
-            Err(err) => {
-                log!("Parser error: {:#}", err);
-                return Err(anyhow::anyhow!("{:#}", err));
-            }
-        };
+        let symbols = self.index_symbols(code, &program, &mapper, version);
 
         // Collect all the available entry functions
         let mut entries = Vec::new();
+        let mut functions = Vec::new();
+        let mut flows = Vec::new();
         let env_offset = environment.get_functions().len() as u16;
         for (i, func) in program.functions().iter().enumerate() {
-            if func.is_entry() {
-                let mapping = mapper
-                    .functions()
-                    .get_function(&(i as u16 + env_offset))
-                    .unwrap();
-                let parameters = mapping
-                    .parameters
-                    .iter()
-                    .map(|(name, _type)| Parameter {
-                        name: name.to_string(),
-                        ty: _type.clone(),
-                    })
-                    .collect();
+            let mapping = mapper
+                .functions()
+                .get_function(&(i as u16 + env_offset))
+                .unwrap();
+
+            let parameters: Vec<Parameter> = mapping
+                .parameters
+                .iter()
+                .map(|(name, _type)| Parameter {
+                    name: name.to_string(),
+                    ty: _type.clone(),
+                })
+                .collect();
+
+            // Declaration of the function in the source
+            let symbol = symbols.functions[i].map(|id| &symbols.symbols[id]);
 
+            if func.is_entry() {
                 entries.push(Entry {
                     id: entries.len(),
                     chunk_id: i as u16,
                     name: mapping.name.to_owned(),
-                    parameters,
+                    parameters: parameters.clone(),
+                    // Entries always return their exit code
+                    return_type: func.return_type().as_ref().unwrap_or(&Type::U64).to_string(),
+                    doc: symbol.and_then(|s| s.doc.clone()),
+                    executable: true,
                 });
             }
+
+            functions.push(FunctionInfo {
+                name: mapping.name.to_owned(),
+                parameters,
+                return_type: func.return_type().clone(),
+                doc: symbol.and_then(|s| s.doc.clone()),
+            });
+            flows.push(warnings::FunctionFlow::new(symbols.functions[i], func));
         }
 
+        let mut warnings = warnings::collect(&symbols, &index, &flows);
+        log!("Found {} warnings", warnings.len());
+
         log!("Found {} entry points", entries.len());
         let mut compiler = Compiler::new(&program, environment.environment());
-        if self.selected_version >= ContractVersion::V1 {
+        if version >= ContractVersion::V1 {
             compiler = compiler.with_enforce_public_parameters(true);
         }
 
-        let module = compiler.compile()?;
+        let module = compiler.compile()
+            .map_err(|err| Diagnostic::error(DiagnosticPhase::Compile, format!("{:#}", err), None))?;
 
         log!("Compiled module");
-        let abi = xelis_abi::abi_from_parse(&program, &mapper, &environment)
-          .unwrap_or_else(|err| format!("{{\"error\": \"{}\"}}", err));
+        // The module stays usable without ABI, the failure is reported as a warning
+        let abi = match xelis_abi::abi_from_parse(&program, &mapper, &environment) {
+            Ok(abi) => Some(abi),
+            Err(err) => {
+                let diagnostic = Diagnostic::new(
+                    DiagnosticPhase::Abi,
+                    DiagnosticSeverity::Warning,
+                    format!("failed to generate the ABI: {:#}", err),
+                    None,
+                ).with_code("abi_unavailable");
+                log!("{}", diagnostic);
+                warnings.push(diagnostic);
+                None
+            }
+        };
 
-        Ok(Program {
+        let mut program = Program {
             module,
             entries,
             abi,
-        })
+            warnings,
+            source_map: Vec::new(),
+            functions,
+            syscalls: Some(self.env_functions(version)
+                .into_iter()
+                .map(|f| (f.syscall_id, f))
+                .collect()),
+        };
+
+        let listing = program.listing()
+            .map_err(|e| Diagnostic::error(DiagnosticPhase::Compile, e, None))?;
+        program.source_map = source_map::build(&listing, &symbols, &index, &self.syscall_names(version));
+        Ok(program)
     }
 
     // Compile the code
+    // On failure, a Diagnostic locating the error is thrown
     pub fn compile(&self, code: &str) -> Result<Program, JsValue> {
-        match self.compile_internal(code) {
-            Ok(program) => Ok(program),
-            Err(err) => Err(JsValue::from_str(&format!("{:#}", err))),
-        }
+        self.compile_internal(code)
+            .map_err(JsValue::from)
     }
 
-    // Set the contract version
-    pub fn set_contract_version(&mut self, version: u8) -> Result<(), JsValue> {
-        let contract_version = ContractVersion::from_bytes(&[version])
-            .map_err(|_| JsValue::from_str("Invalid contract version"))?;
+    // Environment function name of each syscall id
+    fn syscall_names(&self, version: ContractVersion) -> HashMap<u16, String> {
+        self.env_functions(version)
+            .into_iter()
+            .map(|f| (f.syscall_id, f.name))
+            .collect()
+    }
 
-        self.selected_version = contract_version;
-        Ok(())
+    // Whether the code is accepted by the lexer and the parser
+    fn parses(&self, code: &str) -> bool {
+        self.parse(code, self.selected_version).is_ok()
     }
 
-    pub fn get_contract_version(&self) -> u8 {
-        self.selected_version as u8
+    // Functions provided by the environment of the selected version
+    fn environment(&self) -> Environment {
+        self.environment_of(self.selected_version)
     }
 
-    pub fn available_contract_versions(&self) -> Vec<u8> {
-        ContractVersion::variants()
-            .into_iter()
-            .map(|v| v as u8)
-            .collect()
+    fn environment_of(&self, version: ContractVersion) -> Environment {
+        Environment {
+            functions: self.env_functions(version),
+            const_functions: self.constants_functions(version),
+            opaque_types: self.environments[&version]
+                .get_opaque_manager()
+                .iter()
+                .map(|ty| ty.name().to_string())
+                .collect(),
+        }
     }
 
-    // Check if a program is running
-    pub fn has_program_running(&self) -> bool {
-        self.is_running.load(Ordering::Relaxed)
+    // Environment function an occurrence refers to
+    fn env_function(&self, target: &Target) -> Option<Func> {
+        self.environment()
+            .function(target)
+            .cloned()
     }
 
-    pub fn get_env_functions(&self) -> Vec<Func> {
-        let mapper = self.environments[&self.selected_version].get_functions_mapper();
-        let mut funcs = Vec::new();
+    fn compile_project_internal(&self, files: &HashMap<String, String>, entry: &str) -> CompileResult {
+        let bundle = project::bundle(files, entry);
+        if !bundle.diagnostics.is_empty() {
+            return CompileResult {
+                program: None,
+                diagnostics: bundle.diagnostics,
+            };
+        }
 
-        for (_t, list) in mapper.get_declared_functions() {
-            for (f, syscall_id) in list {
-                let params: Vec<String> = f.parameters.iter().map(|(name, ty)| 
-                    format!("{}: {}", name, ty)
-                ).collect();
+        let mut result = self.compile_recovering(&bundle.source, self.selected_version);
+        result.diagnostics = result.diagnostics
+            .into_iter()
+            .map(|d| bundle.locate(d, files))
+            .collect();
 
-                funcs.push(Func {
-                    name: f.name.to_string(),
-                    on_type: f.on_type.as_ref().map(Type::to_string),
-                    on_instance: f.require_instance && f.on_type.is_some(),
-                    return_type: f.return_type.as_ref().map(Type::to_string),
-                    params,
-                    syscall_id,
-                    cost: f.cost,
-                    comment: f.comment.map(str::to_owned),
-                });
-            }
+        // The source map still refers to the bundled source
+        if let Some(program) = result.program.as_mut() {
+            program.warnings = std::mem::take(&mut program.warnings)
+                .into_iter()
+                .map(|d| bundle.locate(d, files))
+                .collect();
         }
 
-        funcs
+        result
     }
 
-    pub fn get_declared_types(&self) -> Vec<String> {
-        let environment = &self.environments[&self.selected_version];
-        let mut types = Vec::new();
-        for ty in environment.get_struct_manager().iter() {
-            let t = ty.get_type();
-            types.push(format!("struct {} {{ {} }}", t.name(), t.fields()
+    // Compile a project made of several files
+    // `files` is an object mapping each file path to its source code,
+    // imports are resolved relatively to the file importing them
+    pub fn compile_project(&self, files: JsValue, entry: &str) -> Result<CompileResult, JsValue> {
+        let files: HashMap<String, String> = serde_wasm_bindgen::from_value(files)
+            .map_err(|e| JsValue::from_str(&format!("Failed to parse project files: {}", e)))?;
+
+        Ok(self.compile_project_internal(&files, entry))
+    }
+
+    // Disassemble each chunk of the program separately
+    // Chunks are named after their Silex function and syscalls after the environment
+    // the program was compiled with, or the selected one for a loaded program
+    pub fn disassemble_chunks(&self, program: &Program) -> Result<Vec<ChunkDisassembly>, JsValue> {
+        let listing = program.listing()
+            .map_err(|e| JsValue::from_str(&e))?;
+
+        let syscall_names = match &program.syscalls {
+            Some(syscalls) => syscalls
+                .iter()
+                .map(|(id, f)| (*id, f.name.clone()))
+                .collect(),
+            None => self.syscall_names(self.selected_version),
+        };
+
+        Ok(disassembly::build(
+            &program.module,
+            &listing,
+            &program.chunk_names(),
+            &syscall_names,
+        ))
+    }
+
+    // Replace by spaces the code responsible of an error, keeping lines and columns intact
+    // An error in the body of a function only removes the body so its signature stays
+    // available to the callers, the whole declaration is removed if the stub still fails
+    fn blank_erroneous_code(code: &mut String, phase: DiagnosticPhase, span: Span) -> Recovery {
+        let offset = span.start_offset();
+        let tokens = syntax::scan(code);
+        let item = syntax::top_level_items(code, &tokens)
+            .into_iter()
+            .find(|(start, end)| *start <= offset && (offset < *end || *end == code.len()));
+
+        let function = item.and_then(|(start, end)| syntax::function_parts(&tokens, start, end));
+        let (start, end) = if phase == DiagnosticPhase::Lex && offset < span.end_offset() {
+            // Skip the invalid character
+            (offset, span.end_offset())
+        } else {
+            let (body_start, body_end) = function.as_ref().map(|parts| parts.body).unwrap_or_default();
+            let in_body = body_start <= offset && offset < body_end && !is_blank(&code[body_start..body_end]);
+            match (item, &function) {
+                (_, Some(parts)) if in_body => parts.body,
+                (Some(item), _) => item,
+                (None, _) => (offset, code[offset..].find('\n').map(|i| offset + i).unwrap_or(code.len())),
+            }
+        };
+
+        if is_blank(&code[start..end]) {
+            return Recovery::Failed;
+        }
+
+        // Each character is replaced by as many spaces as its bytes so the offsets are kept
+        let blank: String = code[start..end]
+            .chars()
+            .map(|c| match c {
+                '\n' | '\r' => c.to_string(),
+                c => " ".repeat(c.len_utf8()),
+            })
+            .collect();
+        code.replace_range(start..end, &blank);
+
+        match function {
+            Some(parts) if Some((start, end)) == item => Recovery::Removed(parts.name),
+            Some(parts) if (start, end) == parts.body => Recovery::Stubbed(parts.name),
+            _ => Recovery::Blanked,
+        }
+    }
+
+    // Run the lexer and the parser, removing the faulty code after each error until the rest parses
+    // Nothing is compiled while recovering, the errors are located on the original code
+    fn parse_recovering(&self, code: &str, version: ContractVersion) -> Recovered {
+        let index = LineIndex::new(code);
+        let mut working = code.to_owned();
+        let mut diagnostics = Vec::new();
+        // Functions whose body was removed to recover
+        let mut stubbed: Vec<String> = Vec::new();
+        // Functions removed to recover, their uses are not reported again
+        let mut removed: Vec<String> = Vec::new();
+
+        loop {
+            let diagnostic = match self.parse(&working, version) {
+                Ok(_) => {
+                    return Recovered {
+                        source: working,
+                        diagnostics,
+                        parsed: true,
+                    };
+                }
+                Err(diagnostic) => diagnostic,
+            };
+
+            // An error located on the name of a removed function is caused by the recovery
+            let mut cascading = diagnostic.span()
+                .and_then(|span| working.get(span.start_offset()..span.end_offset()))
+                .map(|text| removed.iter().any(|name| name == text.trim()))
+                .unwrap_or(false);
+
+            let recovery = match (diagnostic.phase(), diagnostic.span()) {
+                (DiagnosticPhase::Lex | DiagnosticPhase::Parse, Some(span)) if diagnostics.len() + 1 < MAX_RECOVERED_ERRORS => {
+                    Self::blank_erroneous_code(&mut working, diagnostic.phase(), span)
+                }
+                _ => Recovery::Failed,
+            };
+
+            // The stub of a function still fails, its error was already reported
+            cascading |= matches!(&recovery, Recovery::Removed(name) if stubbed.contains(name));
+            if !cascading {
+                // Blanking keeps the offsets, only the columns may have changed
+                let span = diagnostic.span().map(|span| index.span(span.start_offset(), span.end_offset()));
+                diagnostics.push(diagnostic.with_span(span));
+            }
+
+            match recovery {
+                Recovery::Failed => {
+                    return Recovered {
+                        source: working,
+                        diagnostics,
+                        parsed: false,
+                    };
+                }
+                Recovery::Stubbed(name) => stubbed.push(name),
+                Recovery::Removed(name) => removed.push(name),
+                Recovery::Blanked => {}
+            }
+        }
+    }
+
+    // Compile the code, recovering from the lexer and parser errors
+    // by removing the faulty code, what's left is compiled once for its warnings
+    fn compile_recovering(&self, code: &str, version: ContractVersion) -> CompileResult {
+        let index = LineIndex::new(code);
+        let recovered = self.parse_recovering(code, version);
+        let mut diagnostics = recovered.diagnostics;
+        if !recovered.parsed {
+            return CompileResult {
+                program: None,
+                diagnostics,
+            };
+        }
+
+        let relocate = |diagnostic: Diagnostic| {
+            let span = diagnostic.span().map(|span| index.span(span.start_offset(), span.end_offset()));
+            diagnostic.with_span(span)
+        };
+
+        let program = match self.compile_for_version(&recovered.source, version) {
+            Ok(program) => {
+                diagnostics.extend(program.warnings().into_iter().map(relocate));
+                Some(program)
+            }
+            Err(diagnostic) => {
+                diagnostics.push(relocate(diagnostic));
+                None
+            }
+        };
+
+        CompileResult {
+            // Some code was removed to recover, the program is incomplete
+            program: program.filter(|_| diagnostics.iter().all(|d| !d.is_error())),
+            diagnostics,
+        }
+    }
+
+    // Index of the symbols of a parsed code
+    fn index_symbols(&self, code: &str, program: &AstProgram, mapper: &GlobalMapper, version: ContractVersion) -> SymbolIndex {
+        let environment = &self.environments[&version];
+        let opaque = |name: &str| environment.get_opaque_by_name(name).cloned().map(Type::Opaque);
+        SymbolIndex::build(code, &Parsed {
+            program,
+            mapper,
+            env_offset: environment.get_functions().len() as u16,
+            opaque: &opaque,
+        })
+    }
+
+    // Index of the symbols of the code once the code failing to parse is removed
+    // It is empty if nothing can be recovered
+    fn symbol_index(&self, code: &str) -> SymbolIndex {
+        let version = self.selected_version;
+        let recovered = self.parse_recovering(code, version);
+        if !recovered.parsed {
+            return SymbolIndex::default();
+        }
+
+        let Ok((program, mapper)) = self.parse(&recovered.source, version) else {
+            return SymbolIndex::default();
+        };
+
+        // The blanked code keeps the offsets of the original one
+        let mut index = self.index_symbols(&recovered.source, &program, &mapper, version);
+        index.recovered = !recovered.diagnostics.is_empty();
+        index
+    }
+
+    // Compile the code without stopping at the first error
+    // All the problems found are reported in the result
+    pub fn compile_with_diagnostics(&self, code: &str) -> CompileResult {
+        self.compile_recovering(code, self.selected_version)
+    }
+
+    // Compile the code for every available contract version
+    // The environment functions called by each compiled module are compared
+    // with every version, independently of the selected one
+    pub fn compile_all_versions(&self, code: &str) -> Vec<VersionReport> {
+        let compiled: Vec<(ContractVersion, CompileResult)> = ContractVersion::variants()
+            .into_iter()
+            .map(|version| {
+                let result = match self.compile_for_version(code, version) {
+                    Ok(program) => CompileResult {
+                        diagnostics: program.warnings(),
+                        program: Some(program),
+                    },
+                    Err(diagnostic) => CompileResult {
+                        program: None,
+                        diagnostics: vec![diagnostic],
+                    },
+                };
+
+                (version, result)
+            })
+            .collect();
+
+        let used: Vec<Vec<Func>> = compiled
+            .iter()
+            .filter_map(|(version, result)| result.program
+                .as_ref()
+                .map(|program| versions::used_syscalls(program, &self.env_functions(*version))))
+            .collect();
+
+        compiled
+            .into_iter()
+            .map(|(version, result)| VersionReport::new(
+                version as u8,
+                result,
+                versions::syscall_changes(&used, &self.env_functions(version)),
+            ))
+            .collect()
+    }
+
+    // Assemble a module from its disassembly text format, as produced by `Program::to_asm`
+    // Entries are the public chunks of the module as no ABI is available
+    pub fn assemble(&self, asm: &str) -> Result<Program, JsValue> {
+        log!("Assembling code:\n{}", asm);
+        let module = Assembler::new(asm)
+            .assemble()
+            .map_err(|err| Diagnostic::error(DiagnosticPhase::Assemble, format!("{:#}", err), None))?;
+
+        Program::from_module(module, None, self.selected_version)
+    }
+
+    // Tokens of the code as produced by the lexer, comments and invalid code included
+    pub fn tokenize(&self, code: &str) -> Vec<SourceToken> {
+        tokens::tokenize(code)
+    }
+
+    // Top level declarations of the code, available even if it doesn't compile
+    pub fn outline(&self, code: &str) -> Vec<OutlineItem> {
+        let index = self.symbol_index(code);
+        outline::build(&index, &LineIndex::new(code))
+    }
+
+    // Declaration of the identifier at the byte offset
+    pub fn definition(&self, code: &str, offset: usize) -> Option<Definition> {
+        let index = self.symbol_index(code);
+        navigation::definition(&index, &LineIndex::new(code), offset, |target| self.env_function(target))
+    }
+
+    // Every use of the identifier at the byte offset
+    pub fn references(&self, code: &str, offset: usize, include_declaration: bool) -> Vec<Span> {
+        let index = self.symbol_index(code);
+        navigation::references(&index, &LineIndex::new(code), offset, include_declaration)
+    }
+
+    // Type, signature and documentation of the identifier at the byte offset
+    pub fn hover(&self, code: &str, offset: usize) -> Option<Hover> {
+        let index = self.symbol_index(code);
+        let environment = &self.environments[&self.selected_version];
+        let opaque = |name: &str| environment.get_opaque_by_name(name).cloned().map(Type::Opaque);
+        hover::hover(&index, &LineIndex::new(code), code, offset, |target| self.env_function(target), &opaque)
+    }
+
+    // Suggestions for the code at the byte offset
+    pub fn complete(&self, code: &str, offset: usize) -> Vec<CompletionItem> {
+        let index = self.symbol_index(code);
+        completion::complete(&index, code, offset, &self.environment())
+    }
+
+    // Parameters of the call in which the cursor is
+    pub fn signature_help(&self, code: &str, offset: usize) -> Option<SignatureHelp> {
+        let index = self.symbol_index(code);
+        signature_help::signature_help(&index, code, offset, &self.environment())
+    }
+
+    // Re-print the code in the canonical style
+    pub fn format(&self, code: &str) -> Result<String, JsValue> {
+        self.format_code(code)
+            .map_err(JsValue::from)
+    }
+
+    // Whether the code is already in the canonical style
+    pub fn check_format(&self, code: &str) -> Result<bool, JsValue> {
+        Ok(self.format(code)? == code)
+    }
+
+    // Classification of every identifier by what it refers to
+    pub fn semantic_tokens(&self, code: &str) -> Vec<SemanticToken> {
+        let index = self.symbol_index(code);
+        semantic::build(&index, &LineIndex::new(code), &self.environment())
+    }
+
+    // Edits renaming the symbol at the byte offset everywhere in the code
+    pub fn rename(&self, code: &str, offset: usize, new_name: &str) -> Result<Vec<TextEdit>, JsValue> {
+        self.rename_edits(code, offset, new_name)
+            .map_err(|err| JsValue::from_str(&err))
+    }
+
+    // Set the contract version
+    pub fn set_contract_version(&mut self, version: u8) -> Result<(), JsValue> {
+        let contract_version = ContractVersion::from_bytes(&[version])
+            .map_err(|_| JsValue::from_str("Invalid contract version"))?;
+
+        self.selected_version = contract_version;
+        Ok(())
+    }
+
+    pub fn get_contract_version(&self) -> u8 {
+        self.selected_version as u8
+    }
+
+    pub fn available_contract_versions(&self) -> Vec<u8> {
+        ContractVersion::variants()
+            .into_iter()
+            .map(|v| v as u8)
+            .collect()
+    }
+
+    // Check if a program is running
+    pub fn has_program_running(&self) -> bool {
+        self.is_running.load(Ordering::Relaxed)
+    }
+
+    pub fn get_env_functions(&self) -> Vec<Func> {
+        self.env_functions(self.selected_version)
+    }
+
+    pub fn get_constants_functions(&self) -> Vec<ConstFunc> {
+        self.constants_functions(self.selected_version)
+    }
+
+    fn env_functions(&self, version: ContractVersion) -> Vec<Func> {
+        let mapper = self.environments[&version].get_functions_mapper();
+        let mut funcs = Vec::new();
+
+        for (_t, list) in mapper.get_declared_functions() {
+            for (f, syscall_id) in list {
+                let params: Vec<String> = f.parameters.iter().map(|(name, ty)| 
+                    format!("{}: {}", name, ty)
+                ).collect();
+
+                funcs.push(Func {
+                    name: f.name.to_string(),
+                    on_type: f.on_type.as_ref().map(Type::to_string),
+                    on_instance: f.require_instance && f.on_type.is_some(),
+                    return_type: f.return_type.as_ref().map(Type::to_string),
+                    params,
+                    syscall_id,
+                    cost: f.cost,
+                    comment: f.comment.map(str::to_owned),
+                });
+            }
+        }
+
+        funcs
+    }
+
+    pub fn get_declared_types(&self) -> Vec<String> {
+        let environment = &self.environments[&self.selected_version];
+        let mut types = Vec::new();
+        for ty in environment.get_struct_manager().iter() {
+            let t = ty.get_type();
+            types.push(format!("struct {} {{ {} }}", t.name(), t.fields()
                 .iter()
                 .map(|(n, ty)| format!("{}: {}", n, ty))
                 .collect::<Vec<_>>()
@@ -635,8 +1583,8 @@ impl Silex {
         types
     }
 
-    pub fn get_constants_functions(&self) -> Vec<ConstFunc> {
-        let environment = &self.environments[&self.selected_version];
+    fn constants_functions(&self, version: ContractVersion) -> Vec<ConstFunc> {
+        let environment = &self.environments[&version];
         let mut funcs = Vec::new();
         for (for_type, mappings) in environment.get_const_functions_mapper().get_mappings() {
             for (name, const_fn) in mappings.iter() {
@@ -656,11 +1604,11 @@ impl Silex {
         funcs
     }
 
-    fn parse_str_to_number<T: std::str::FromStr>(value: &str) -> Result<T, JsValue> {
+    fn parse_str_to_number<T: std::str::FromStr>(value: &str) -> Result<T, String> {
         value
             .trim()
             .parse::<T>()
-            .map_err(|_| JsValue::from_str("Failed to parse the value as a number"))
+            .map_err(|_| "Failed to parse the value as a number".to_owned())
     }
 
     fn js_value_to_string(value: JsValue) -> Result<String, JsValue> {
@@ -719,19 +1667,19 @@ impl Silex {
         open: char,
         close: char,
         expected: &str,
-    ) -> Result<&'a str, JsValue> {
+    ) -> Result<&'a str, String> {
         let value = value.trim();
         if !value.starts_with(open) || !value.ends_with(close) {
-            return Err(JsValue::from_str(&format!(
+            return Err(format!(
                 "Expected {} value wrapped in {}{}",
                 expected, open, close
-            )));
+            ));
         }
 
         Ok(&value[open.len_utf8()..value.len() - close.len_utf8()])
     }
 
-    fn find_top_level_delimiter(value: &str, delimiter: char) -> Result<Option<usize>, JsValue> {
+    fn find_top_level_delimiter(value: &str, delimiter: char) -> Result<Option<usize>, String> {
         let mut stack = Vec::new();
         let mut quote = None;
         let mut escaped = false;
@@ -755,9 +1703,7 @@ impl Silex {
                 '(' => stack.push(')'),
                 ']' | '}' | ')' => {
                     if stack.pop() != Some(ch) {
-                        return Err(JsValue::from_str(
-                            "Mismatched delimiters in parameter value",
-                        ));
+                        return Err("Mismatched delimiters in parameter value".to_owned());
                     }
                 }
                 _ if ch == delimiter && stack.is_empty() => return Ok(Some(index)),
@@ -766,13 +1712,13 @@ impl Silex {
         }
 
         if quote.is_some() || !stack.is_empty() {
-            return Err(JsValue::from_str("Unclosed delimiter in parameter value"));
+            return Err("Unclosed delimiter in parameter value".to_owned());
         }
 
         Ok(None)
     }
 
-    fn split_top_level(value: &str, delimiter: char) -> Result<Vec<&str>, JsValue> {
+    fn split_top_level(value: &str, delimiter: char) -> Result<Vec<&str>, String> {
         let value = value.trim();
         if value.is_empty() {
             return Ok(Vec::new());
@@ -803,15 +1749,13 @@ impl Silex {
                 '(' => stack.push(')'),
                 ']' | '}' | ')' => {
                     if stack.pop() != Some(ch) {
-                        return Err(JsValue::from_str(
-                            "Mismatched delimiters in parameter value",
-                        ));
+                        return Err("Mismatched delimiters in parameter value".to_owned());
                     }
                 }
                 _ if ch == delimiter && stack.is_empty() => {
                     let part = value[start..index].trim();
                     if part.is_empty() {
-                        return Err(JsValue::from_str("Empty item in parameter value"));
+                        return Err("Empty item in parameter value".to_owned());
                     }
 
                     parts.push(part);
@@ -822,19 +1766,19 @@ impl Silex {
         }
 
         if quote.is_some() || !stack.is_empty() {
-            return Err(JsValue::from_str("Unclosed delimiter in parameter value"));
+            return Err("Unclosed delimiter in parameter value".to_owned());
         }
 
         let part = value[start..].trim();
         if part.is_empty() {
-            return Err(JsValue::from_str("Empty trailing item in parameter value"));
+            return Err("Empty trailing item in parameter value".to_owned());
         }
 
         parts.push(part);
         Ok(parts)
     }
 
-    fn parse_text_to_primitive(value: &str, param: &Type) -> Result<Primitive, JsValue> {
+    fn parse_text_to_primitive(value: &str, param: &Type) -> Result<Primitive, String> {
         Ok(match param {
             Type::U8 => Primitive::U8(Self::parse_str_to_number(value)?),
             Type::U16 => Primitive::U16(Self::parse_str_to_number(value)?),
@@ -847,12 +1791,12 @@ impl Silex {
                 value
                     .trim()
                     .parse::<bool>()
-                    .map_err(|_| JsValue::from_str("Failed to parse as bool value"))?,
+                    .map_err(|_| "Failed to parse as bool value".to_owned())?,
             ),
             Type::Range(inner) => {
                 let parts: Vec<&str> = value.split("..").collect();
                 if parts.len() != 2 {
-                    return Err(JsValue::from_str("Invalid range format"));
+                    return Err("Invalid range format".to_owned());
                 }
 
                 let start = Self::parse_text_to_primitive(parts[0], inner)?;
@@ -862,10 +1806,10 @@ impl Silex {
             }
             Type::Function(_) => Primitive::U16(Self::parse_str_to_number(value)?),
             _ => {
-                return Err(JsValue::from_str(&format!(
+                return Err(format!(
                     "Unsupported parameter type parsing: {}",
                     param
-                )));
+                ));
             }
         })
     }
@@ -875,15 +1819,15 @@ impl Silex {
         value: &str,
         fields: &[(Cow<'static, str>, Type)],
         owner: &str,
-    ) -> Result<Vec<ValueCell>, JsValue> {
+    ) -> Result<Vec<ValueCell>, String> {
         let parts = Self::split_top_level(value, ',')?;
         if parts.len() != fields.len() {
-            return Err(JsValue::from_str(&format!(
+            return Err(format!(
                 "Invalid field count for {}: expected {}, got {}",
                 owner,
                 fields.len(),
                 parts.len()
-            )));
+            ));
         }
 
         parts
@@ -893,10 +1837,10 @@ impl Silex {
                 let value = if let Some(index) = Self::find_top_level_delimiter(part, ':')? {
                     let name = part[..index].trim();
                     if name != field_name.as_ref() {
-                        return Err(JsValue::from_str(&format!(
+                        return Err(format!(
                             "Invalid field name for {}: expected `{}`, got `{}`",
                             owner, field_name, name
-                        )));
+                        ));
                     }
 
                     &part[index + ':'.len_utf8()..]
@@ -909,7 +1853,7 @@ impl Silex {
             .collect()
     }
 
-    fn parse_array_text(&self, value: &str, inner: &Type) -> Result<ValueCell, JsValue> {
+    fn parse_array_text(&self, value: &str, inner: &Type) -> Result<ValueCell, String> {
         let value = Self::strip_wrapping(value, '[', ']', "array")?;
         let values = Self::split_top_level(value, ',')?
             .into_iter()
@@ -919,7 +1863,7 @@ impl Silex {
         Ok(ValueCell::Object(values))
     }
 
-    fn parse_tuple_text(&self, value: &str, types: &[Type]) -> Result<ValueCell, JsValue> {
+    fn parse_tuple_text(&self, value: &str, types: &[Type]) -> Result<ValueCell, String> {
         let value = value.trim();
         let value = if value.starts_with('(') {
             Self::strip_wrapping(value, '(', ')', "tuple")?
@@ -929,11 +1873,11 @@ impl Silex {
 
         let parts = Self::split_top_level(value, ',')?;
         if parts.len() != types.len() {
-            return Err(JsValue::from_str(&format!(
+            return Err(format!(
                 "Invalid tuple item count: expected {}, got {}",
                 types.len(),
                 parts.len()
-            )));
+            ));
         }
 
         let values = parts
@@ -950,13 +1894,13 @@ impl Silex {
         value: &str,
         key_type: &Type,
         value_type: &Type,
-    ) -> Result<ValueCell, JsValue> {
+    ) -> Result<ValueCell, String> {
         let value = Self::strip_wrapping(value, '{', '}', "map")?;
         let mut map = IndexMap::new();
 
         for part in Self::split_top_level(value, ',')? {
             let index = Self::find_top_level_delimiter(part, ':')?
-                .ok_or_else(|| JsValue::from_str("Expected `key: value` map entry"))?;
+                .ok_or_else(|| "Expected `key: value` map entry".to_owned())?;
             let key = self.parse_text_to_const(part[..index].trim(), key_type)?;
             let value =
                 self.parse_text_to_const(part[index + ':'.len_utf8()..].trim(), value_type)?;
@@ -971,7 +1915,7 @@ impl Silex {
         &self,
         value: &str,
         ty: &xelis_types::StructType,
-    ) -> Result<ValueCell, JsValue> {
+    ) -> Result<ValueCell, String> {
         let value = Self::strip_wrapping(value, '{', '}', ty.name())?;
         let values = self
             .parse_record_fields(value, ty.fields(), ty.name())?
@@ -986,7 +1930,7 @@ impl Silex {
         &self,
         value: &str,
         ty: &xelis_types::EnumType,
-    ) -> Result<ValueCell, JsValue> {
+    ) -> Result<ValueCell, String> {
         let value = value.trim();
         let value = value
             .rsplit_once("::")
@@ -1013,15 +1957,15 @@ impl Silex {
             .enumerate()
             .find(|(_, (name, _))| name.as_ref() == variant_name)
             .ok_or_else(|| {
-                JsValue::from_str(&format!(
+                format!(
                     "Unknown enum variant `{}` for {}",
                     variant_name,
                     ty.name()
-                ))
+                )
             })?;
 
         let variant_id = u8::try_from(variant_id)
-            .map_err(|_| JsValue::from_str("Enum variant id exceeds u8"))?;
+            .map_err(|_| "Enum variant id exceeds u8".to_owned())?;
 
         let mut values = vec![Primitive::U8(variant_id).into()];
         if variant.fields().is_empty() {
@@ -1029,17 +1973,17 @@ impl Silex {
                 .map(|value| !value.trim().is_empty())
                 .unwrap_or(false)
             {
-                return Err(JsValue::from_str(&format!(
+                return Err(format!(
                     "Enum variant `{}` does not accept fields",
                     variant_name
-                )));
+                ));
             }
 
             return Ok(ValueCell::Object(values));
         }
 
         let payload = payload.ok_or_else(|| {
-            JsValue::from_str(&format!("Enum variant `{}` requires fields", variant_name))
+            format!("Enum variant `{}` requires fields", variant_name)
         })?;
 
         values.extend(
@@ -1051,7 +1995,7 @@ impl Silex {
         Ok(ValueCell::Object(values))
     }
 
-    fn parse_untyped_text(&self, value: &str) -> Result<ValueCell, JsValue> {
+    fn parse_untyped_text(&self, value: &str) -> Result<ValueCell, String> {
         let trimmed = value.trim();
         if trimmed.eq_ignore_ascii_case("null") || trimmed.is_empty() {
             return Ok(Primitive::Null.into());
@@ -1080,7 +2024,7 @@ impl Silex {
         Ok(Primitive::String(value.to_owned()).into())
     }
 
-    fn parse_text_to_const(&self, value: &str, param: &Type) -> Result<ValueCell, JsValue> {
+    fn parse_text_to_const(&self, value: &str, param: &Type) -> Result<ValueCell, String> {
         Ok(match param {
             Type::Optional(ty) => {
                 let trimmed = value.trim();
@@ -1095,32 +2039,32 @@ impl Silex {
                 let value = Self::parse_string_literal(value);
                 let value = value.strip_prefix("0x").unwrap_or(&value);
                 let bytes = hex::decode(value)
-                    .map_err(|_| JsValue::from_str("Failed to parse as blob (hex) value"))?;
+                    .map_err(|_| "Failed to parse as blob (hex) value".to_owned())?;
                 ValueCell::Bytes(bytes)
             }
             Type::Opaque(ty) => {
                 let environment = &self.environments[&self.selected_version];
                 let name = environment
                     .get_opaque_name(ty)
-                    .ok_or_else(|| JsValue::from_str("Failed to get opaque name"))?;
+                    .ok_or_else(|| "Failed to get opaque name".to_owned())?;
 
                 match name {
                     "Hash" => {
                         let value = Self::parse_string_literal(value);
                         let hash = Hash::from_hex(&value)
-                            .map_err(|_| JsValue::from_str("Failed to parse as hash value"))?;
+                            .map_err(|_| "Failed to parse as hash value".to_owned())?;
                         Primitive::Opaque(hash.into()).into()
                     }
                     "Ciphertext" => {
                         let value = Self::parse_string_literal(value);
                         let ciphertext = CiphertextCache::from_hex(&value)
-                            .map_err(|_| JsValue::from_str("Failed to parse as ciphertext value"))?;
+                            .map_err(|_| "Failed to parse as ciphertext value".to_owned())?;
                         Primitive::Opaque(ciphertext.into()).into()
                     }
                     "Address" => {
                         let value = Self::parse_string_literal(value);
                         let address = Address::from_string(&value).map_err(|e| {
-                            JsValue::from_str(&format!("Failed to parse as address value: {}", e))
+                            format!("Failed to parse as address value: {}", e)
                         })?;
 
                         Primitive::Opaque(address.into()).into()
@@ -1128,14 +2072,14 @@ impl Silex {
                     "Signature" => {
                         let value = Self::parse_string_literal(value);
                         let signature = Signature::from_hex(&value)
-                            .map_err(|_| JsValue::from_str("Failed to parse as signature value"))?;
+                            .map_err(|_| "Failed to parse as signature value".to_owned())?;
                         Primitive::Opaque(signature.into()).into()
                     }
                     _ => {
-                        return Err(JsValue::from_str(&format!(
+                        return Err(format!(
                             "Unsupported opaque type parsing: {}",
                             name
-                        )))
+                        ))
                     }
                 }
             }
@@ -1162,32 +2106,46 @@ impl Silex {
 
         let value = Self::js_value_to_string(value)?;
         self.parse_text_to_const(&value, param)
+            .map_err(|err| JsValue::from_str(&err))
     }
 
     pub fn js_to_storage_preset(&self, js_value: JsValue) -> Result<StoragePreset, JsValue> {
         let storage_preset_json: Result<StoragePresetJSON, serde_wasm_bindgen::Error> = serde_wasm_bindgen::from_value(js_value);
         match storage_preset_json {
-            Ok(sp_json) => {
-                let Some(key_type) = Type::primitive_type_from_byte(sp_json.key_type_id) else {
-                    return Err(JsValue::from_str("Invalid key type"));
-                };
+            Ok(sp_json) => self.parse_storage_preset(sp_json)
+                .map_err(|err| JsValue::from_str(&err)),
+            Err(err) => Err(JsValue::from_str(format!("Failed to parse storage preset: {}", err).as_str())),
+        }
+    }
 
-                let Some(value_type) = Type::primitive_type_from_byte(sp_json.value_type_id) else {
-                    return Err(JsValue::from_str("Invalid value type"));
-                };
+    fn parse_storage_preset(&self, sp_json: StoragePresetJSON) -> Result<StoragePreset, String> {
+        let Some(key_type) = Type::primitive_type_from_byte(sp_json.key_type_id) else {
+            return Err("Invalid key type".to_owned());
+        };
 
-                let storage_key = self.parse_js_value_to_const(JsValue::from_str(sp_json.key.as_str()), &key_type)?;
-                let storage_value = self.parse_js_value_to_const(JsValue::from_str(sp_json.value.as_str()), &value_type)?;
+        let Some(value_type) = Type::primitive_type_from_byte(sp_json.value_type_id) else {
+            return Err("Invalid value type".to_owned());
+        };
 
-                let sp = StoragePreset {
-                    key: storage_key,
-                    value: storage_value,
-                };
+        Ok(StoragePreset {
+            key: self.parse_text_to_const(&sp_json.key, &key_type)?,
+            value: self.parse_text_to_const(&sp_json.value, &value_type)?,
+        })
+    }
 
-                Ok(sp)
-            }
-            Err(err) => Err(JsValue::from_str(format!("Failed to parse storage preset: {}", err).as_str())),
+    // Deposits given as asset hash to amount
+    fn parse_deposits(deposits_map: HashMap<String, String>) -> Result<IndexMap<Hash, ContractDeposit>, String> {
+        let mut deposits = IndexMap::new();
+        for (hash_str, amount_str) in deposits_map {
+            let hash = Hash::from_hex(&hash_str)
+                .map_err(|e| format!("Invalid hash format: {}", e))?;
+            let amount = amount_str.parse::<u64>()
+                .map_err(|e| format!("Invalid amount: {}", e))?;
+
+            deposits.insert(hash, ContractDeposit::Public(amount));
         }
+
+        Ok(deposits)
     }
 
     async fn execute_program_internal(
@@ -1208,6 +2166,8 @@ impl Silex {
             .collect::<HashMap<_, _>>();
 
         let selected_version = self.selected_version;
+        // Each instance collects the logs of its own executions
+        let log_sender = LogSender(self.logs_sender.clone());
         tokio::task::spawn_blocking(move || {
             log!("Building storage and chain state");
             // Fake storage
@@ -1324,6 +2284,7 @@ impl Silex {
                 let context = vm.context_mut();
                 context.insert_ref(&storage);
                 context.insert_mut(&mut chain_state);
+                context.insert_ref(&log_sender);
 
                 if let Some(max_gas) = max_gas {
                     context.set_gas_limit(max_gas);
@@ -1433,6 +2394,10 @@ impl Silex {
             .get(entry_id)
             .ok_or_else(|| JsValue::from_str("Invalid entry point"))?;
 
+        if !entry.executable {
+            return Err(JsValue::from_str(&format!("The parameters of `{}` are unknown without an ABI", entry.name)));
+        }
+
         if entry.parameters.len() != params.len() {
             return Err(JsValue::from_str("Invalid number of parameters"));
         }
@@ -1455,23 +2420,30 @@ impl Silex {
             // Deserialize the JS object as a HashMap
             let deposits_map: HashMap<String, String> = serde_wasm_bindgen::from_value(deposits_js)
                 .map_err(|e| JsValue::from_str(&format!("Failed to parse deposits: {:?}", e)))?;
-            
-            for (hash_str, amount_str) in deposits_map {
-                let hash = Hash::from_hex(&hash_str)
-                    .map_err(|e| JsValue::from_str(&format!("Invalid hash format: {}", e)))?;
-                let amount = amount_str.parse::<u64>()
-                    .map_err(|e| JsValue::from_str(&format!("Invalid amount: {}", e)))?;
-                
-                deposits.insert(hash, ContractDeposit::Public(amount));
-            }
+
+            deposits = Self::parse_deposits(deposits_map)
+                .map_err(|err| JsValue::from_str(&err))?;
         }
 
+        self.run_entry(program, entry.chunk_id, max_gas, deposits, values, sp_list, run_constructor).await
+            .map_err(|err| JsValue::from_str(&err))
+    }
+
+    async fn run_entry(
+        &self,
+        program: Program,
+        chunk_id: u16,
+        max_gas: Option<u64>,
+        deposits: IndexMap<Hash, ContractDeposit>,
+        values: Vec<ValueCell>,
+        sp_list: Vec<StoragePreset>,
+        run_constructor: bool,
+    ) -> Result<ExecutionResult, String> {
         // Mark it as running
         self.is_running.store(true, Ordering::Relaxed);
 
-        let chunk_id = entry.chunk_id;
         let handle = self.execute_program_internal(program, chunk_id, max_gas, deposits, values, sp_list, run_constructor).await
-            .map_err(|err| JsValue::from_str(&format!("{:#}", err)));
+            .map_err(|err| format!("{:#}", err));
 
         // Mark it as not running
         self.is_running.store(false, Ordering::Relaxed);
@@ -1489,11 +2461,92 @@ impl Silex {
     }
 }
 
+// Rust API for the native tools, errors are not converted to JS values
+impl Silex {
+    // Re-print the code in the canonical style
+    pub fn format_code(&self, code: &str) -> Result<String, Diagnostic> {
+        let expected = format::lexer_tokens(code)
+            .map_err(|err| Diagnostic::error(DiagnosticPhase::Lex, err, None))?;
+
+        let formatted = format::format(code)
+            .map_err(|err| Diagnostic::error(DiagnosticPhase::Lex, err, None))?;
+
+        if format::lexer_tokens(&formatted).ok() != Some(expected) {
+            return Err(Diagnostic::error(
+                DiagnosticPhase::Lex,
+                "Formatting would change the tokens of the code",
+                None,
+            ));
+        }
+
+        if self.parses(code) && !self.parses(&formatted) {
+            return Err(Diagnostic::error(
+                DiagnosticPhase::Parse,
+                "Formatting would make the code fail to parse",
+                None,
+            ));
+        }
+
+        Ok(formatted)
+    }
+
+    // Execute an entry with its parameters written as text
+    pub async fn execute_entry(
+        &self,
+        program: Program,
+        entry_id: usize,
+        max_gas: Option<u64>,
+        params: &[String],
+        storage_presets: Vec<StoragePresetJSON>,
+        deposits: HashMap<String, String>,
+        run_constructor: bool,
+    ) -> Result<ExecutionResult, String> {
+        if self.has_program_running() {
+            return Err("A program is already running".to_owned());
+        }
+
+        let entry = program
+            .entries
+            .get(entry_id)
+            .ok_or_else(|| "Invalid entry point".to_owned())?;
+
+        if !entry.executable {
+            return Err(format!("The parameters of `{}` are unknown without an ABI", entry.name));
+        }
+
+        if entry.parameters.len() != params.len() {
+            return Err(format!("Invalid number of parameters: expected {}, got {}", entry.parameters.len(), params.len()));
+        }
+
+        let values = params
+            .iter()
+            .zip(entry.parameters.iter())
+            .map(|(value, param)| self.parse_text_to_const(value, &param.ty)
+                .map_err(|err| format!("Invalid value for parameter `{}`: {}", param.name, err)))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let sp_list = storage_presets
+            .into_iter()
+            .map(|preset| self.parse_storage_preset(preset))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let deposits = Self::parse_deposits(deposits)?;
+        let chunk_id = entry.chunk_id;
+        self.run_entry(program, chunk_id, max_gas, deposits, values, sp_list, run_constructor).await
+    }
+
+    // Edits renaming the symbol at the byte offset everywhere in the code
+    pub fn rename_edits(&self, code: &str, offset: usize, new_name: &str) -> Result<Vec<TextEdit>, String> {
+        let index = self.symbol_index(code);
+        rename::rename(&index, &LineIndex::new(code), offset, new_name, &self.environment())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::borrow::Cow;
 
-    use xelis_common::config::MAX_GAS_USAGE_PER_TX;
+    use xelis_bytecode::OpCode;
     use xelis_types::{EnumVariant, StructType};
 
     use super::*;
@@ -1535,30 +2588,1061 @@ mod tests {
     }
 
     #[test]
-    fn test_parse_string_array_parameter() {
-        let silex = Silex::new();
-        let ty = Type::Array(Box::new(Type::String));
+    fn test_parse_error_diagnostic() {
+        let code = "entry main() {\n    let a: u64 = ;\n    return 0;\n}";
 
-        let value = silex
-            .parse_text_to_const("[hello, world]", &ty)
-            .expect("Failed to parse string array");
+        let silex = Silex::new();
+        let diagnostic = silex
+            .compile_internal(code)
+            .err()
+            .expect("Expected a compile error");
 
-        assert_eq!(
-            value,
-            ValueCell::Object(vec![
-                Primitive::String("hello".to_owned()).into(),
-                Primitive::String("world".to_owned()).into(),
-            ])
-        );
+        assert_eq!(diagnostic.phase(), DiagnosticPhase::Parse);
+        assert!(diagnostic.is_error());
+        assert_eq!(diagnostic.start_line(), Some(2));
     }
 
     #[test]
-    fn test_parse_nested_parameter_combinations() {
-        let silex = Silex::new();
-        let ty = Type::Map(
-            Box::new(Type::String),
-            Box::new(Type::Array(Box::new(Type::U64))),
-        );
+    fn test_lex_error_diagnostic() {
+        let code = "entry main() {\n    let a: u64 = 1 § 2;\n    return a;\n}";
+
+        let silex = Silex::new();
+        let diagnostic = silex
+            .compile_internal(code)
+            .err()
+            .expect("Expected a compile error");
+
+        // The error is on the invalid character, not up to the end of its line
+        assert_eq!(diagnostic.phase(), DiagnosticPhase::Lex);
+        assert_eq!(diagnostic.start_line(), Some(2));
+        let span = diagnostic.span().expect("No span for the lexer error");
+        assert_eq!(&code[span.start_offset()..span.end_offset()], "§");
+    }
+
+    #[test]
+    fn test_compile_reports_multiple_errors() {
+        let code = r#"
+            fn first() -> u64 {
+                let a: u64 = ;
+                return a;
+            }
+
+            fn second() -> u64 {
+                return 1 +;
+            }
+
+            entry main() {
+                return 0;
+            }
+        "#;
+
+        let silex = Silex::new();
+        let result = silex.compile_with_diagnostics(code);
+
+        assert!(!result.is_success());
+        assert_eq!(result.error_count(), 2);
+
+        let lines: Vec<_> = result.diagnostics()
+            .iter()
+            .filter_map(Diagnostic::start_line)
+            .collect();
+        assert_eq!(lines, vec![3, 8]);
+    }
+
+    #[test]
+    fn test_compile_recovery_keeps_called_functions() {
+        let code = r#"
+            fn helper(a: u64) -> u64 {
+                return a +;
+            }
+
+            entry main() {
+                return helper(1);
+            }
+
+            entry other() {
+                let b: u64 = ;
+                return b;
+            }
+        "#;
+
+        let silex = Silex::new();
+        let result = silex.compile_with_diagnostics(code);
+
+        assert!(!result.is_success());
+        assert_eq!(result.error_count(), 2);
+
+        // The call to the broken function is not reported
+        let lines: Vec<_> = result.diagnostics()
+            .iter()
+            .filter_map(Diagnostic::start_line)
+            .collect();
+        assert_eq!(lines, vec![3, 11]);
+    }
+
+    #[test]
+    fn test_compile_warnings() {
+        let code = r#"
+            fn helper(a: u64) -> u64 {
+                return 1;
+            }
+
+            entry main(value: u64) {
+                let unused: u64 = 5;
+                return value;
+            }
+        "#;
+
+        let silex = Silex::new();
+        let program = silex
+            .compile_internal(code)
+            .expect("Failed to compile the program");
+
+        let codes: Vec<_> = program.warnings()
+            .iter()
+            .filter_map(Diagnostic::code)
+            .collect();
+
+        assert_eq!(codes, vec![
+            "unused_function",
+            "unused_parameter",
+            "unused_variable",
+        ]);
+    }
+
+    #[test]
+    fn test_warning_missing_exit_code_on_fall_through() {
+        let code = r#"
+            entry main(c: bool) {
+                if c {
+                    return 0;
+                }
+            }
+
+            entry other(c: bool) {
+                if c {
+                    return 0;
+                } else {
+                    return 1;
+                }
+            }
+        "#;
+
+        let silex = Silex::new();
+        let program = silex
+            .compile_internal(code)
+            .expect("Failed to compile the program");
+
+        let warnings: Vec<_> = program.warnings()
+            .iter()
+            .filter(|w| w.code().as_deref() == Some("missing_exit_code"))
+            .filter_map(Diagnostic::start_line)
+            .collect();
+
+        assert_eq!(warnings, vec![2]);
+    }
+
+    #[test]
+    fn test_compile_all_versions() {
+        let code = r#"
+            entry main(values: u64[]) {
+                return values.len() as u64;
+            }
+        "#;
+
+        let silex = Silex::new();
+        let reports = silex.compile_all_versions(code);
+        let versions: Vec<u8> = reports.iter().map(VersionReport::version).collect();
+        assert_eq!(versions, silex.available_contract_versions());
+
+        let selected = reports.iter()
+            .find(|r| r.version() == silex.get_contract_version())
+            .expect("No report for the selected version");
+        assert!(selected.compiles());
+        assert!(selected.module_size().unwrap() > 0);
+        assert!(selected.missing_syscalls().is_empty());
+        assert!(selected.changed_syscalls().is_empty());
+
+        // The reports don't depend on the selected version
+        let mut other = Silex::new();
+        other.set_contract_version(versions[0]).expect("Invalid contract version");
+        let changes = |reports: &[VersionReport]| -> Vec<_> {
+            reports.iter()
+                .map(|r| (r.version(), r.missing_syscalls(), r.changed_syscalls()))
+                .collect()
+        };
+        assert_eq!(changes(&other.compile_all_versions(code)), changes(&reports));
+
+        // Reports for a code that doesn't compile keep the diagnostics
+        let reports = silex.compile_all_versions("entry main() { return x; }");
+        assert!(reports.iter().all(|r| !r.compiles() && r.module_size().is_none() && !r.diagnostics().is_empty()));
+    }
+
+    #[test]
+    fn test_syscall_changes_of_overloads() {
+        let func = |params: &[&str], syscall_id| Func {
+            name: "max".to_owned(),
+            on_type: None,
+            on_instance: false,
+            return_type: Some("u64".to_owned()),
+            params: params.iter().map(|p| p.to_string()).collect(),
+            syscall_id,
+            cost: 1,
+            comment: None,
+        };
+
+        let used = vec![vec![func(&["a: u64", "b: u64"], 0), func(&["a: u8", "b: u8"], 1)]];
+
+        // Each overload is compared with the one of the same parameter types
+        let functions = vec![func(&["a: u8", "b: u8"], 1), func(&["a: u64", "b: u64"], 0)];
+        assert_eq!(versions::syscall_changes(&used, &functions), (vec![], vec![]));
+
+        let functions = vec![func(&["a: u64", "b: u64"], 2)];
+        assert_eq!(
+            versions::syscall_changes(&used, &functions),
+            (vec!["max(u8, u8)".to_owned()], vec!["max(u64, u64)".to_owned()])
+        );
+    }
+
+    #[test]
+    fn test_deploy_payload() {
+        let code = r#"
+            hook constructor() {
+                return 0;
+            }
+
+            entry main() {
+                return 0;
+            }
+        "#;
+
+        let silex = Silex::new();
+        let program = silex
+            .compile_internal(code)
+            .expect("Failed to compile the program");
+
+        let deposits = HashMap::from([(XELIS_ASSET.to_hex(), "100".to_owned())]);
+        let payload = program
+            .build_deploy_payload(silex.get_contract_version(), Some(1000), deposits)
+            .expect("Failed to build the payload");
+
+        assert!(payload.has_constructor());
+        assert_eq!(payload.to_hex(), hex::encode(payload.to_bytes()));
+        let data = TransactionType::from_bytes(&payload.to_bytes()).expect("Invalid payload bytes");
+        let TransactionType::DeployContract(deploy) = &data else {
+            panic!("Expected a deploy contract payload");
+        };
+        assert_eq!(deploy.invoke.as_ref().map(|invoke| invoke.max_gas), Some(1000));
+        assert!(serde_json::from_str::<serde_json::Value>(&payload.to_json()).is_ok());
+
+        assert!(program.build_deploy_payload(u8::MAX, None, HashMap::new()).is_err());
+        assert!(payload.contract_hash("00").is_err());
+    }
+
+    #[test]
+    fn test_verify_program() {
+        let code = r#"
+            fn unused() -> u64 {
+                return 1;
+            }
+
+            fn countdown(n: u64) -> u64 {
+                if n == 0 {
+                    return 0;
+                }
+                return countdown(n - 1);
+            }
+
+            entry main() {
+                println("start");
+                return countdown(3);
+            }
+        "#;
+
+        let silex = Silex::new();
+        let program = silex
+            .compile_internal(code)
+            .expect("Failed to compile the program");
+
+        let report = program.verify();
+        assert!(report.is_valid(), "{}", report.to_json());
+
+        let codes: Vec<(String, usize)> = report.findings()
+            .iter()
+            .map(|f| (f.code(), f.chunk_id()))
+            .collect();
+        assert_eq!(codes, vec![
+            ("unreachable_chunk".to_owned(), 0),
+            ("recursive_call".to_owned(), 1),
+        ]);
+
+        let chunks = report.chunks();
+        assert_eq!(chunks.len(), 3);
+        assert!(!chunks[0].is_reachable());
+        assert!(chunks[1].is_reachable() && chunks[2].is_reachable());
+        assert_eq!(chunks[1].calls(), vec![1]);
+        assert_eq!(chunks[2].calls(), vec![1]);
+        assert!(chunks.iter().all(|c| c.max_stack_depth() > 0));
+    }
+
+    #[test]
+    fn test_verify_loops_and_constructors() {
+        let code = r#"
+            struct Point {
+                x: u64,
+                y: u64
+            }
+
+            entry main() {
+                let total: u64 = 0;
+                for i: u64 = 0; i < 10; i += 1 {
+                    let p: Point = Point { x: i, y: total };
+                    if p.x > 5 {
+                        break;
+                    }
+                    total += p.x + p.y;
+                }
+                return total;
+            }
+        "#;
+
+        let silex = Silex::new();
+        let program = silex
+            .compile_internal(code)
+            .expect("Failed to compile the program");
+
+        // Jumps are byte offsets, each one lands on an instruction
+        let report = program.verify();
+        assert!(report.is_valid(), "{}", report.to_json());
+        assert!(report.chunks()[0].max_stack_depth() >= 2);
+
+        // An opcode that can't be decoded is an error
+        let mut chunks = listing::decode(&program.module);
+        chunks[0].error = Some(listing::DecodeError {
+            offset: chunks[0].size,
+            message: "unknown opcode 0xff".to_owned(),
+        });
+        let report = verify::verify(&program.module, &chunks, &program.chunk_names(), program.syscalls.as_ref());
+        assert!(!report.is_valid());
+        assert!(report.findings().iter().any(|f| f.code() == "invalid_opcode" && f.is_error()));
+
+        // Popping from the empty stack is an error
+        let mut chunks = listing::decode(&program.module);
+        chunks[0].instructions[0].opcode = OpCode::Pop;
+        chunks[0].instructions[0].operands.clear();
+        let report = verify::verify(&program.module, &chunks, &program.chunk_names(), program.syscalls.as_ref());
+        assert!(report.findings().iter().any(|f| f.code() == "stack_underflow" && f.instruction() == Some(0)));
+    }
+
+    #[test]
+    fn test_entries_and_hooks() {
+        let code = r#"
+            hook constructor() {
+                return 0;
+            }
+
+            // Add two numbers
+            entry add(a: u64, b: u64) {
+                return a + b;
+            }
+        "#;
+
+        let silex = Silex::new();
+        let program = silex
+            .compile_internal(code)
+            .expect("Failed to compile the program");
+
+        let entries = program.entries();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].name(), "add");
+        assert_eq!(entries[0].chunk_id(), 1);
+        assert_eq!(entries[0].return_type(), "u64");
+        assert_eq!(entries[0].doc().as_deref(), Some("Add two numbers"));
+
+        let hooks = program.hooks();
+        assert_eq!(hooks.len(), 1);
+        assert_eq!(hooks[0].id(), 0);
+        assert_eq!(hooks[0].name(), "constructor");
+        assert_eq!(hooks[0].chunk_id(), 0);
+        assert!(hooks[0].parameters().is_empty());
+    }
+
+    #[test]
+    fn test_load_program_from_bytes() {
+        let code = r#"
+            entry main(a: u64, b: string) {
+                println(b);
+                return a;
+            }
+        "#;
+
+        let silex = Silex::new();
+        let program = silex
+            .compile_internal(code)
+            .expect("Failed to compile the program");
+
+        let loaded = Program::from_hex(&program.to_hex(), None, None)
+            .ok()
+            .expect("Failed to load the module");
+
+        assert_eq!(loaded.to_bytes(), program.to_bytes());
+        assert_eq!(loaded.to_asm(), program.to_asm());
+        assert_eq!(loaded.entries().len(), 1);
+        assert_eq!(loaded.entries()[0].chunk_id, program.entries()[0].chunk_id);
+        // Parameters are unknown without the ABI
+        assert!(program.entries()[0].is_executable());
+        assert!(!loaded.entries()[0].is_executable());
+
+        // The ABI is only known from the compilation
+        assert!(program.abi_value().unwrap().is_some());
+        assert!(program.warnings().iter().all(|w| w.phase() != DiagnosticPhase::Abi));
+        assert_eq!(loaded.to_abi(), "");
+        assert_eq!(loaded.abi_value(), Ok(None));
+    }
+
+    #[test]
+    fn test_assemble_disassembled_program() {
+        let code = r#"
+            fn double(a: u64) -> u64 {
+                return a * 2;
+            }
+
+            entry main() {
+                return double(21) - 42;
+            }
+        "#;
+
+        let silex = Silex::new();
+        let program = silex
+            .compile_internal(code)
+            .expect("Failed to compile the program");
+
+        let assembled = silex
+            .assemble(&program.to_asm())
+            .ok()
+            .expect("Failed to assemble the program");
+
+        assert_eq!(assembled.to_bytes(), program.to_bytes());
+        assert_eq!(assembled.entries().len(), 1);
+    }
+
+    #[test]
+    fn test_source_map_points_to_calls() {
+        let code = r#"
+            entry main() {
+                let a: u64 = 10;
+                println("first");
+                let b: u64 = a * 2;
+                println("second");
+                return b - 20;
+            }
+        "#;
+
+        let silex = Silex::new();
+        let program = silex
+            .compile_internal(code)
+            .expect("Failed to compile the program");
+
+        let entry = &program.entries()[0];
+        let calls: Vec<_> = program.source_map()
+            .iter()
+            .filter(|e| e.chunk_id() == entry.chunk_id as usize)
+            .map(|e| {
+                let span = e.span();
+                assert_eq!(e.instruction_end(), e.instruction_start() + 1);
+                assert_eq!(program.source_location(e.chunk_id(), e.instruction_start()), Some(span));
+                (span.start_line(), span.start_column(), &code[span.start_offset()..span.end_offset()])
+            })
+            .collect();
+
+        // Only the call instructions are located
+        assert_eq!(calls, vec![(4, 17, "println"), (6, 17, "println")]);
+        let first = &program.source_map()[0];
+        assert_eq!(program.source_location(entry.chunk_id as usize, first.instruction_start() + 1), None);
+    }
+
+    #[test]
+    fn test_module_stats() {
+        let code = r#"
+            struct Point {
+                x: u64,
+                y: u64
+            }
+
+            fn sum(p: Point) -> u64 {
+                return p.x + p.y;
+            }
+
+            entry main() {
+                let p: Point = Point { x: 1, y: 2 };
+                println(sum(p));
+                return 0;
+            }
+        "#;
+
+        let silex = Silex::new();
+        let program = silex
+            .compile_internal(code)
+            .expect("Failed to compile the program");
+
+        let stats = program.stats().expect("Failed to decode the module");
+        assert_eq!(stats.chunk_count(), 2);
+        assert_eq!(stats.structs_count(), 1);
+        assert_eq!(stats.syscall_count(), 1);
+        assert_eq!(stats.byte_size(), program.to_bytes().len());
+        assert_eq!(
+            stats.instruction_count(),
+            stats.opcodes().iter().map(OpcodeCount::count).sum::<usize>()
+        );
+    }
+
+    #[test]
+    fn test_disassemble_chunks_with_names() {
+        let code = r#"
+            fn greet() {
+                println("hello");
+            }
+
+            hook constructor() {
+                return 0;
+            }
+
+            entry main() {
+                greet();
+                return 0;
+            }
+        "#;
+
+        let silex = Silex::new();
+        let program = silex
+            .compile_internal(code)
+            .expect("Failed to compile the program");
+
+        let chunks = silex.disassemble_chunks(&program).expect("Failed to decode the module");
+        let names: Vec<_> = chunks
+            .iter()
+            .map(|c| (c.name().unwrap_or_default(), c.kind()))
+            .collect();
+
+        assert_eq!(names, vec![
+            ("greet".to_owned(), "function".to_owned()),
+            ("constructor".to_owned(), "hook".to_owned()),
+            ("main".to_owned(), "entry".to_owned()),
+        ]);
+        assert!(chunks[0].text().contains("; println"));
+        assert!(chunks[2].text().contains("; greet"));
+    }
+
+    #[test]
+    fn test_disassemble_chunks_uses_program_version() {
+        let code = r#"
+            entry main() {
+                println("hello");
+                return 0;
+            }
+        "#;
+
+        let mut silex = Silex::new();
+        let versions = silex.available_contract_versions();
+        silex.set_contract_version(versions[0]).expect("Invalid contract version");
+        let program = silex
+            .compile_internal(code)
+            .expect("Failed to compile the program");
+
+        // Switching the selected version doesn't change the syscalls of the program
+        silex.set_contract_version(*versions.last().unwrap()).expect("Invalid contract version");
+        let chunks = silex.disassemble_chunks(&program).expect("Failed to decode the module");
+        assert!(chunks[0].text().contains("; println"));
+    }
+
+    #[test]
+    fn test_tokenize() {
+        let code = "// answer\nlet x: u64 = 42 # 1;";
+        let tokens = Silex::new().tokenize(code);
+        let categories: Vec<_> = tokens
+            .iter()
+            .map(|t| (t.category(), t.text()))
+            .collect();
+
+        assert_eq!(categories, vec![
+            ("comment".to_owned(), "// answer".to_owned()),
+            ("keyword".to_owned(), "let".to_owned()),
+            ("identifier".to_owned(), "x".to_owned()),
+            ("punctuation".to_owned(), ":".to_owned()),
+            ("keyword".to_owned(), "u64".to_owned()),
+            ("operator".to_owned(), "=".to_owned()),
+            ("number".to_owned(), "42".to_owned()),
+            ("error".to_owned(), "#".to_owned()),
+            ("number".to_owned(), "1".to_owned()),
+            ("punctuation".to_owned(), ";".to_owned()),
+        ]);
+
+        let error = &tokens[7];
+        assert_eq!(error.kind(), "Error");
+        assert_eq!((error.span().start_line(), error.span().start_column()), (2, 17));
+    }
+
+    #[test]
+    fn test_outline() {
+        let code = r#"
+            struct Point { x: u64, y: u64 }
+
+            enum Shape { Empty, Dot { at: Point } }
+
+            const ORIGIN: u64 = 0;
+
+            // Distance on the x axis
+            fn (self Point) dx(other: Point) -> u64 {
+                return self.x - other.x
+            }
+
+            entry main() {
+                let p: Point = Point { x: 1, y: 2 };
+                return p.dx(p) +
+            }
+        "#;
+
+        let outline = Silex::new().outline(code);
+        let items: Vec<_> = outline
+            .iter()
+            .map(|i| (i.kind(), i.signature()))
+            .collect();
+
+        assert_eq!(items, vec![
+            ("struct".to_owned(), "struct Point { x: u64, y: u64 }".to_owned()),
+            ("enum".to_owned(), "enum Shape { Empty, Dot { at: Point } }".to_owned()),
+            ("constant".to_owned(), "const ORIGIN: u64 = 0".to_owned()),
+            ("function".to_owned(), "fn (self Point) dx(other: Point) -> u64".to_owned()),
+            ("entry".to_owned(), "entry main()".to_owned()),
+        ]);
+
+        assert_eq!(outline[1].children().len(), 2);
+        assert_eq!(outline[3].doc().as_deref(), Some("Distance on the x axis"));
+        assert_eq!(outline[4].name_span().start_line(), 13);
+    }
+
+    #[test]
+    fn test_definition_and_references() {
+        let code = r#"
+            struct Point { x: u64, y: u64 }
+
+            entry main() {
+                let p: Point = Point { x: 1, y: 2 };
+                let sum: u64 = p.x + p.y;
+                println(sum);
+                return p.x
+            }
+        "#;
+
+        let silex = Silex::new();
+        let usage = code.find("p.x").unwrap() + 2;
+        let definition = silex.definition(code, usage).expect("no definition");
+        assert_eq!(definition.kind(), "field");
+        assert_eq!(definition.span().map(|s| s.start_line()), Some(2));
+
+        let references = silex.references(code, usage, true);
+        let lines: Vec<_> = references.iter().map(|s| s.start_line()).collect();
+        assert_eq!(lines, vec![2, 5, 6, 8]);
+
+        let local = code.find("sum)").unwrap();
+        assert_eq!(silex.references(code, local, false).len(), 1);
+
+        let builtin = silex
+            .definition(code, code.find("println").unwrap())
+            .expect("no builtin definition");
+        assert!(builtin.is_builtin());
+        assert_eq!(builtin.function().map(|f| f.name()), Some("println".to_owned()));
+    }
+
+    #[test]
+    fn test_hover() {
+        let code = r#"
+            const LIMIT: u64 = 10;
+
+            // Double the value
+            fn double(value: u64) -> u64 {
+                return value * 2
+            }
+
+            entry main() {
+                let result: u64 = double(LIMIT);
+                println(result);
+                return 0
+            }
+        "#;
+
+        let silex = Silex::new();
+        let function = silex
+            .hover(code, code.find("double(LIMIT)").unwrap())
+            .expect("no hover for the function");
+        assert_eq!(function.signature().as_deref(), Some("fn double(value: u64) -> u64"));
+        assert_eq!(function.type_name().as_deref(), Some("u64"));
+        assert_eq!(function.doc().as_deref(), Some("Double the value"));
+
+        let constant = silex
+            .hover(code, code.find("LIMIT)").unwrap())
+            .expect("no hover for the constant");
+        assert_eq!(constant.value().as_deref(), Some("10"));
+
+        let local = silex
+            .hover(code, code.find("result)").unwrap())
+            .expect("no hover for the variable");
+        assert_eq!((local.kind(), local.type_name()), ("variable".to_owned(), Some("u64".to_owned())));
+
+        let builtin = silex
+            .hover(code, code.find("println").unwrap())
+            .expect("no hover for the builtin");
+        assert_eq!(builtin.kind(), "builtin");
+        assert!(builtin.syscall_id().is_some());
+        assert!(builtin.markdown().contains("fn println("));
+    }
+
+    #[test]
+    fn test_hover_expression() {
+        let code = r#"
+            struct Point { x: u64, y: u64 }
+
+            fn origin() -> Point {
+                return Point { x: 0, y: 0 }
+            }
+
+            entry main() {
+                let values: u64[] = [1, 2];
+                return values[0] + origin().x
+            }
+        "#;
+
+        let silex = Silex::new();
+        let index = silex
+            .hover(code, code.find("[0]").unwrap() + 1)
+            .expect("no hover for the index");
+        assert_eq!(index.kind(), "expression");
+        assert_eq!((index.name(), index.type_name()), ("values[0]".to_owned(), Some("u64".to_owned())));
+
+        let call = silex
+            .hover(code, code.find("().x").unwrap() + 1)
+            .expect("no hover for the call");
+        assert_eq!((call.name(), call.type_name()), ("origin()".to_owned(), Some("Point".to_owned())));
+    }
+
+    #[test]
+    fn test_hover_method_on_unknown_receiver() {
+        let code = r#"
+            fn broken() -> u64 {
+                return unknown().len()
+            }
+
+            entry main() {
+                let values: u64[] = [1, 2];
+                return values.len()
+            }
+        "#;
+
+        let silex = Silex::new();
+        // The body failing to parse is not indexed, no builtin named `len` must be shown
+        assert!(silex.hover(code, code.find("len()").unwrap()).is_none());
+
+        let method = silex
+            .hover(code, code.rfind("len()").unwrap())
+            .expect("no hover for the method");
+        assert_eq!(method.kind(), "builtin");
+    }
+
+    #[test]
+    fn test_completion() {
+        let code = r#"
+            struct Point { x: u64, y: u64 }
+
+            enum Direction { Up, Down }
+
+            fn (self Point) norm() -> u64 {
+                return self.x + self.y
+            }
+
+            entry main(amount: u64) {
+                let point: Point = Point { x: 1, y: 2 };
+                let d: Direction = Direction::
+                let total: u64 = point.
+                return am
+            }
+        "#;
+
+        let silex = Silex::new();
+        let labels = |marker: &str| -> Vec<String> {
+            let offset = code.find(marker).unwrap() + marker.len();
+            silex.complete(code, offset)
+                .iter()
+                .map(|i| i.label())
+                .collect()
+        };
+
+        assert_eq!(labels("Direction::"), vec!["Up", "Down"]);
+
+        let members = labels("point.");
+        assert_eq!(&members[..3], &["x", "y", "norm"]);
+
+        assert_eq!(labels("return am"), vec!["amount"]);
+
+        let offset = code.find("let total").unwrap();
+        let visible: Vec<_> = silex.complete(code, offset)
+            .into_iter()
+            .map(|i| (i.label(), i.kind()))
+            .collect();
+        assert!(visible.contains(&("point".to_owned(), "variable".to_owned())));
+        assert!(visible.contains(&("println".to_owned(), "builtin".to_owned())));
+        assert!(!visible.iter().any(|(label, _)| label == "total"));
+    }
+
+    #[test]
+    fn test_completion_inside_multibyte_character() {
+        let code = "entry main() {\n    let é: u64 = 1;\n    return é\n}";
+        let offset = code.rfind('é').unwrap() + 1;
+
+        let labels: Vec<_> = Silex::new()
+            .complete(code, offset)
+            .iter()
+            .map(|i| i.label())
+            .collect();
+        assert!(!labels.is_empty());
+
+        let index = LineIndex::new(code);
+        assert_eq!(index.position(offset), (3, 12));
+    }
+
+    #[test]
+    fn test_signature_help() {
+        let code = r#"
+            fn add(left: u64, right: u64) -> u64 {
+                return left + right
+            }
+
+            entry main() {
+                let value: u64 = add(1, add(2, 3), 4);
+                println(value);
+                return 0
+            }
+        "#;
+
+        let silex = Silex::new();
+        let help = |marker: &str| {
+            let offset = code.find(marker).unwrap() + marker.len();
+            silex.signature_help(code, offset)
+        };
+
+        let inner = help("add(2, ").expect("no help in the inner call");
+        assert_eq!(inner.active_parameter(), 1);
+        assert_eq!(inner.active_type().as_deref(), Some("u64"));
+        assert_eq!(inner.parameters(), vec!["left: u64", "right: u64"]);
+
+        let outer = help("add(2, 3), ").expect("no help in the outer call");
+        assert_eq!(outer.active_parameter(), 2);
+        assert_eq!(outer.active_type(), None);
+
+        let builtin = help("println(").expect("no help for the builtin");
+        assert_eq!(builtin.name(), "println");
+        assert_eq!(builtin.active_parameter(), 0);
+
+        assert!(help("return ").is_none());
+    }
+
+    #[test]
+    fn test_signature_help_after_non_ascii_text() {
+        let code = "entry main() {\n    println(\"héllo wörld\", ";
+        let silex = Silex::new();
+
+        let help = silex
+            .signature_help(code, code.len())
+            .expect("no help after the non-ASCII argument");
+        assert_eq!(help.name(), "println");
+        assert_eq!(help.active_parameter(), 1);
+
+        // Inside the `é` of the first argument
+        let offset = code.find('é').unwrap() + 1;
+        let help = silex
+            .signature_help(code, offset)
+            .expect("no help inside the non-ASCII argument");
+        assert_eq!(help.active_parameter(), 0);
+    }
+
+    #[test]
+    fn test_format() {
+        let code = "struct Point {x:u64,\n  y : u64}\n\n\n// Entry point\nentry main()\n{\nlet p: Point = Point { x: 1, y: 2 };   \n  if !(p.x>0) { return p.y }\n    else {\n  return 0 // nothing\n}\n}";
+        let expected = "struct Point {\n    x: u64,\n    y: u64\n}\n\n// Entry point\nentry main() {\n    let p: Point = Point { x: 1, y: 2 };\n    if !(p.x > 0) { return p.y } else {\n        return 0 // nothing\n    }\n}\n";
+
+        let silex = Silex::new();
+        let formatted = silex.format(code).expect("Failed to format the code");
+        assert_eq!(formatted, expected);
+        assert!(silex.check_format(&formatted).unwrap());
+        assert!(!silex.check_format(code).unwrap());
+    }
+
+    #[test]
+    fn test_semantic_tokens() {
+        let code = r#"
+            struct Account { owner: Hash }
+
+            entry main(amount: u64) {
+                let account: Account = Account { owner: Hash::zero() };
+                println(account.owner);
+                return amount
+            }
+        "#;
+
+        let tokens = Silex::new().semantic_tokens(code);
+        let kind_at = |marker: &str| -> Option<String> {
+            let offset = code.find(marker).unwrap();
+            tokens.iter()
+                .find(|t| t.span().start_offset() == offset)
+                .map(|t| t.token_type())
+        };
+
+        assert_eq!(kind_at("Account {").as_deref(), Some("struct"));
+        assert_eq!(kind_at("Hash }").as_deref(), Some("opaque_type"));
+        assert_eq!(kind_at("main").as_deref(), Some("entry"));
+        assert_eq!(kind_at("u64").as_deref(), Some("builtin_type"));
+        assert_eq!(kind_at("account:").as_deref(), Some("local"));
+        assert_eq!(kind_at("amount\n").as_deref(), Some("parameter"));
+        assert_eq!(kind_at("owner);").as_deref(), Some("field"));
+        assert_eq!(kind_at("println").as_deref(), Some("builtin_function"));
+    }
+
+    #[test]
+    fn test_rename() {
+        let code = r#"
+            fn double(value: u64) -> u64 {
+                return value * 2
+            }
+
+            entry main() {
+                let total: u64 = double(21);
+                let other: u64 = total + 1;
+                return double(other) - 86
+            }
+        "#;
+
+        let silex = Silex::new();
+        let offset = code.find("double").unwrap();
+        let edits = silex.rename_edits(code, offset, "twice").expect("Failed to rename");
+        let lines: Vec<_> = edits.iter().map(|e| e.span().start_line()).collect();
+        assert_eq!(lines, vec![2, 7, 9]);
+        assert!(edits.iter().all(|e| e.new_text() == "twice"));
+
+        let total = code.find("total").unwrap();
+        assert!(silex.rename_edits(code, total, "other").is_err());
+        assert!(silex.rename_edits(code, total, "double").is_err());
+        assert!(silex.rename_edits(code, total, "let").is_err());
+        assert!(silex.rename_edits(code, offset, "println").is_err());
+        assert!(silex.rename_edits(code, offset, "main").is_err());
+        assert_eq!(silex.rename_edits(code, total, "sum").map(|e| e.len()).ok(), Some(2));
+    }
+
+    #[test]
+    fn test_rename_unresolved() {
+        let code = r#"
+            fn double(value: u64) -> u64 {
+                return value * 2
+            }
+
+            entry main() {
+                let total: u64 = double(21);
+                return double(total) +
+            }
+        "#;
+
+        let silex = Silex::new();
+        // The body of `main` doesn't parse, the uses it contains are unknown
+        assert!(silex.rename_edits(code, code.find("double").unwrap(), "twice").is_err());
+        assert!(silex.rename_edits(code, code.find("value").unwrap(), "input").is_err());
+    }
+
+    #[test]
+    fn test_compile_project_with_imports() {
+        let files: HashMap<String, String> = [
+            ("lib/math.slx", "fn double(a: u64) -> u64 {\n    return a * 2;\n}\n"),
+            ("main.slx", "import \"lib/math.slx\";\n\nentry main() {\n    return double(21) - 42;\n}\n"),
+            ("broken.slx", "import \"lib/math.slx\";\nimport \"missing.slx\";\n\nentry main() {\n    return double(1) +;\n}\n"),
+        ]
+            .into_iter()
+            .map(|(path, code)| (path.to_owned(), code.to_owned()))
+            .collect();
+
+        let silex = Silex::new();
+        let result = silex.compile_project_internal(&files, "main.slx");
+        assert!(result.is_success());
+
+        let result = silex.compile_project_internal(&files, "./broken.slx");
+        let diagnostic = &result.diagnostics()[0];
+        assert_eq!(diagnostic.file().as_deref(), Some("broken.slx"));
+        assert_eq!(diagnostic.start_line(), Some(2));
+
+        let files: HashMap<String, String> = files
+            .into_iter()
+            .filter(|(path, _)| path != "main.slx")
+            .map(|(path, code)| (path, code.replace("import \"missing.slx\";", "")))
+            .collect();
+
+        let result = silex.compile_project_internal(&files, "broken.slx");
+        let diagnostic = &result.diagnostics()[0];
+        assert_eq!(diagnostic.phase(), DiagnosticPhase::Parse);
+        assert_eq!(diagnostic.file().as_deref(), Some("broken.slx"));
+        assert_eq!(diagnostic.start_line(), Some(5));
+    }
+
+    #[test]
+    fn test_parse_abi_type_names() {
+        assert_eq!(
+            abi::parse_type_name("map<string, u64[]>", &|_| None),
+            Some(Type::Map(
+                Box::new(Type::String),
+                Box::new(Type::Array(Box::new(Type::U64))),
+            ))
+        );
+        assert_eq!(
+            abi::parse_type_name("optional<(u8, bool)>", &|_| None),
+            Some(Type::Optional(Box::new(Type::Tuples(vec![Type::U8, Type::Bool]))))
+        );
+        assert_eq!(abi::parse_type_name("Hash", &|_| None), None);
+        assert_eq!(abi::parse_type_name("Hash[]", &|_| Some(Type::Bytes)), Some(Type::Array(Box::new(Type::Bytes))));
+    }
+
+    #[test]
+    fn test_line_index_positions() {
+        let code = "fn a() {}\r\nentry main() {\n    return 0;\n}";
+        let index = LineIndex::new(code);
+
+        let offset = index.offset(2, 7);
+        assert_eq!(&code[offset..offset + 4], "main");
+        assert_eq!(index.position(offset), (2, 7));
+
+        let span = index.span_to_line_end(offset);
+        assert_eq!(&code[span.start_offset()..span.end_offset()], "main() {");
+    }
+
+    #[test]
+    fn test_parse_string_array_parameter() {
+        let silex = Silex::new();
+        let ty = Type::Array(Box::new(Type::String));
+
+        let value = silex
+            .parse_text_to_const("[hello, world]", &ty)
+            .expect("Failed to parse string array");
+
+        assert_eq!(
+            value,
+            ValueCell::Object(vec![
+                Primitive::String("hello".to_owned()).into(),
+                Primitive::String("world".to_owned()).into(),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_parse_nested_parameter_combinations() {
+        let silex = Silex::new();
+        let ty = Type::Map(
+            Box::new(Type::String),
+            Box::new(Type::Array(Box::new(Type::U64))),
+        );
 
         let value = silex
             .parse_text_to_const("{first: [1, 2], second: [3]}", &ty)
@@ -1683,4 +3767,37 @@ mod tests {
 
         assert_eq!(result.value(), "0");
     }
+
+    #[tokio::test]
+    async fn test_execute_entry_with_text_parameters() {
+        let code = r#"
+            entry add(a: u64, b: u64) {
+                println("sum");
+                return a + b;
+            }
+        "#;
+
+        let silex = Silex::new();
+        let program = silex
+            .compile_internal(code)
+            .expect("Failed to compile the program");
+        let params = vec!["40".to_owned(), "2".to_owned()];
+        let result = silex
+            .execute_entry(program.clone(), 0, Some(MAX_GAS_USAGE_PER_TX), &params, vec![], HashMap::new(), false)
+            .await
+            .expect("Failed to execute the program");
+
+        assert_eq!(result.value(), "42");
+        let json: serde_json::Value = serde_json::from_str(&result.to_json()).unwrap();
+        assert_eq!(json["value"], "42");
+        assert_eq!(json["is_error"], false);
+        assert_eq!(json["logs"][0], "sum");
+
+        let err = silex
+            .execute_entry(program, 0, None, &["abc".to_owned(), "2".to_owned()], vec![], HashMap::new(), false)
+            .await
+            .err()
+            .expect("Invalid parameter accepted");
+        assert!(err.contains("`a`"));
+    }
 }
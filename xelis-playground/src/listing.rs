@@ -0,0 +1,168 @@
+// Instructions of the compiled chunks, decoded from their bytecode
+// Each opcode is followed by its operands, jumps use byte offsets in the chunk.
+// The size of the operands is the one of the bytecode crate, their layout is only
+// used to read their values and must add up to it.
+
+use xelis_bytecode::{Module, OpCode};
+
+#[derive(Debug, Clone)]
+pub struct Instruction {
+    pub opcode: OpCode,
+    pub operands: Vec<u64>,
+    // Byte offset of the opcode in the chunk
+    pub offset: usize,
+}
+
+impl Instruction {
+    pub fn mnemonic(&self) -> String {
+        format!("{:?}", self.opcode)
+    }
+
+    // Offset, mnemonic and operands, as displayed in a listing
+    pub fn text(&self) -> String {
+        let mut text = format!("{:04x}: {}", self.offset, self.mnemonic());
+        for operand in &self.operands {
+            text.push_str(&format!(" {}", operand));
+        }
+
+        text
+    }
+
+    pub fn operand(&self, index: usize) -> Option<u64> {
+        self.operands.get(index).copied()
+    }
+
+    pub fn is_syscall(&self) -> bool {
+        self.opcode == OpCode::SysCall
+    }
+
+    pub fn is_chunk_call(&self) -> bool {
+        self.opcode == OpCode::InvokeChunk
+    }
+
+    pub fn is_return(&self) -> bool {
+        self.opcode == OpCode::Return
+    }
+
+    // Whether the instruction may jump, and if it may also continue to the next one
+    pub fn jump_kind(&self) -> Option<bool> {
+        match self.opcode {
+            OpCode::Jump => Some(false),
+            OpCode::JumpIfFalse | OpCode::IteratorNext => Some(true),
+            _ => None,
+        }
+    }
+
+    // Byte offset in the chunk a jump goes to
+    pub fn jump_target(&self) -> Option<usize> {
+        self.jump_kind()?;
+        self.operand(0).map(|offset| offset as usize)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DecodeError {
+    // Byte offset in the chunk
+    pub offset: usize,
+    pub message: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct ChunkListing {
+    pub id: usize,
+    pub instructions: Vec<Instruction>,
+    // Size of the chunk in bytes
+    pub size: usize,
+    // Decoding stops at the first invalid byte
+    pub error: Option<DecodeError>,
+}
+
+impl ChunkListing {
+    // Index of the instruction starting at a byte offset
+    pub fn instruction_at(&self, offset: usize) -> Option<usize> {
+        self.instructions
+            .binary_search_by_key(&offset, |instruction| instruction.offset)
+            .ok()
+    }
+}
+
+// Size in bytes of each operand read after the opcode
+fn operand_layout(opcode: OpCode) -> &'static [usize] {
+    match opcode {
+        OpCode::Constant | OpCode::MemoryLoad | OpCode::MemorySet | OpCode::SubLoad | OpCode::ArrayCall => &[2],
+        OpCode::PopN | OpCode::CopyN | OpCode::Swap | OpCode::Cast | OpCode::NewArray | OpCode::NewMap => &[1],
+        OpCode::Swap2 => &[1, 1],
+        OpCode::Jump | OpCode::JumpIfFalse | OpCode::IteratorNext => &[4],
+        // Id, called on a value, arguments count
+        OpCode::InvokeChunk | OpCode::SysCall => &[2, 1, 1],
+        OpCode::NewStruct => &[2],
+        // Enum id, variant id
+        OpCode::NewEnum => &[2, 1],
+        _ => &[],
+    }
+}
+
+fn decode_chunk(id: usize, bytes: &[u8]) -> ChunkListing {
+    let mut instructions = Vec::new();
+    let mut error = None;
+    let mut offset = 0;
+
+    while offset < bytes.len() {
+        let Some(opcode) = OpCode::from_byte(bytes[offset]) else {
+            error = Some(DecodeError {
+                offset,
+                message: format!("unknown opcode 0x{:02x}", bytes[offset]),
+            });
+            break;
+        };
+
+        let end = offset + 1 + opcode.arguments_bytes();
+        let Some(arguments) = bytes.get(offset + 1..end) else {
+            error = Some(DecodeError {
+                offset,
+                message: format!("truncated operands for {:?}", opcode),
+            });
+            break;
+        };
+
+        let layout = operand_layout(opcode);
+        if layout.iter().sum::<usize>() != arguments.len() {
+            error = Some(DecodeError {
+                offset,
+                message: format!("unexpected operands of {} bytes for {:?}", arguments.len(), opcode),
+            });
+            break;
+        }
+
+        let mut cursor = 0;
+        let mut operands = Vec::new();
+        for size in layout {
+            let value = &arguments[cursor..cursor + size];
+            operands.push(value.iter().fold(0u64, |acc, byte| acc << 8 | *byte as u64));
+            cursor += size;
+        }
+
+        instructions.push(Instruction {
+            opcode,
+            operands,
+            offset,
+        });
+        offset = end;
+    }
+
+    ChunkListing {
+        id,
+        instructions,
+        size: bytes.len(),
+        error,
+    }
+}
+
+// Decode every chunk, an invalid chunk keeps the instructions read before the error
+pub fn decode(module: &Module) -> Vec<ChunkListing> {
+    module.chunks()
+        .iter()
+        .enumerate()
+        .map(|(id, chunk)| decode_chunk(id, chunk.get_instructions()))
+        .collect()
+}
@@ -0,0 +1,109 @@
+// Go to definition and find references
+// Identifiers are resolved with the symbol index, functions provided by
+// the environment are resolved to their declaration in the environment.
+
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use crate::{
+    span::{LineIndex, Span},
+    symbols::{SymbolIndex, Target},
+    Func,
+};
+
+#[wasm_bindgen]
+pub struct Definition {
+    name: String,
+    // Symbol kind, `builtin` for an environment function or `type` for a builtin type
+    kind: String,
+    // Location in the source, none for the builtins
+    span: Option<Span>,
+    function: Option<Func>,
+}
+
+#[wasm_bindgen]
+impl Definition {
+    pub fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    pub fn kind(&self) -> String {
+        self.kind.clone()
+    }
+
+    pub fn span(&self) -> Option<Span> {
+        self.span
+    }
+
+    pub fn is_builtin(&self) -> bool {
+        self.span.is_none()
+    }
+
+    // Environment function, if the identifier refers to one
+    pub fn function(&self) -> Option<Func> {
+        self.function.clone()
+    }
+}
+
+pub fn definition(
+    index: &SymbolIndex,
+    lines: &LineIndex,
+    offset: usize,
+    env_function: impl Fn(&Target) -> Option<Func>,
+) -> Option<Definition> {
+    let occurrence = index.occurrence_at(offset)?;
+    match &occurrence.target {
+        Target::Symbol(id) => {
+            let symbol = &index.symbols[*id];
+            Some(Definition {
+                name: symbol.name.clone(),
+                kind: symbol.kind.as_str().to_owned(),
+                span: Some(lines.span(symbol.start, symbol.end)),
+                function: None,
+            })
+        }
+        target @ (Target::Function { name, .. } | Target::Method { name, .. }) => {
+            let function = env_function(target)?;
+            Some(Definition {
+                name: name.clone(),
+                kind: "builtin".to_owned(),
+                span: None,
+                function: Some(function),
+            })
+        }
+        Target::Type(name) => Some(Definition {
+            name: name.clone(),
+            kind: "type".to_owned(),
+            span: None,
+            function: None,
+        }),
+        Target::Unknown => None,
+    }
+}
+
+// Whether two occurrences refer to the same thing
+fn same_target(a: &Target, b: &Target) -> bool {
+    match (a, b) {
+        (Target::Symbol(a), Target::Symbol(b)) => a == b,
+        (Target::Function { name: a, on_type: a_type }, Target::Function { name: b, on_type: b_type }) => a == b && a_type == b_type,
+        (Target::Method { name: a, receiver: a_type }, Target::Method { name: b, receiver: b_type }) => a == b && a_type == b_type,
+        (Target::Type(a), Target::Type(b)) => a == b,
+        _ => false,
+    }
+}
+
+pub fn references(index: &SymbolIndex, lines: &LineIndex, offset: usize, include_declaration: bool) -> Vec<Span> {
+    let Some(occurrence) = index.occurrence_at(offset) else {
+        return Vec::new();
+    };
+
+    if occurrence.target == Target::Unknown {
+        return Vec::new();
+    }
+
+    index.occurrences
+        .iter()
+        .filter(|o| same_target(&o.target, &occurrence.target))
+        .filter(|o| include_declaration || !o.declaration)
+        .map(|o| lines.span(o.start, o.end))
+        .collect()
+}
@@ -0,0 +1,85 @@
+// Outline of the declarations of a source code
+// Signatures are the ones of the symbol index, typed by the parser.
+// Nothing is compiled: the declarations failing to parse are left out.
+
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use crate::{
+    span::{LineIndex, Span},
+    symbols::{SymbolIndex, SymbolKind},
+};
+
+#[wasm_bindgen]
+#[derive(Debug, Clone)]
+pub struct OutlineItem {
+    name: String,
+    kind: SymbolKind,
+    signature: String,
+    doc: Option<String>,
+    // Whole declaration
+    span: Span,
+    // Name of the declaration only
+    name_span: Span,
+    // Fields of a struct or a variant, variants of an enum
+    children: Vec<OutlineItem>,
+}
+
+#[wasm_bindgen]
+impl OutlineItem {
+    pub fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    pub fn kind(&self) -> String {
+        self.kind.as_str().to_owned()
+    }
+
+    pub fn signature(&self) -> String {
+        self.signature.clone()
+    }
+
+    pub fn doc(&self) -> Option<String> {
+        self.doc.clone()
+    }
+
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
+    pub fn name_span(&self) -> Span {
+        self.name_span
+    }
+
+    pub fn children(&self) -> Vec<OutlineItem> {
+        self.children.clone()
+    }
+}
+
+fn item(index: &SymbolIndex, lines: &LineIndex, id: usize) -> OutlineItem {
+    let symbol = &index.symbols[id];
+    let children = match symbol.kind {
+        SymbolKind::Struct | SymbolKind::Enum | SymbolKind::Variant => symbol.children
+            .iter()
+            .map(|child| item(index, lines, *child))
+            .collect(),
+        _ => Vec::new(),
+    };
+
+    OutlineItem {
+        name: symbol.name.clone(),
+        kind: symbol.kind,
+        signature: index.signature(id),
+        doc: symbol.doc.clone(),
+        span: lines.span(symbol.decl_start, symbol.decl_end),
+        name_span: lines.span(symbol.start, symbol.end),
+        children,
+    }
+}
+
+// Top level declarations in source order
+pub fn build(index: &SymbolIndex, lines: &LineIndex) -> Vec<OutlineItem> {
+    index
+        .top_level()
+        .map(|(id, _)| item(index, lines, id))
+        .collect()
+}
@@ -0,0 +1,227 @@
+// Multi-file Silex projects
+// Files are resolved from the entry file by following their imports,
+// then concatenated in dependency order into a single source to compile.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    diagnostic::{Diagnostic, DiagnosticPhase},
+    span::LineIndex,
+    syntax::{self, TokenKind},
+};
+
+// Range of the bundled source coming from a file
+struct Segment {
+    path: String,
+    start: usize,
+    end: usize,
+}
+
+pub struct Bundle {
+    pub source: String,
+    segments: Vec<Segment>,
+    // Problems found while resolving the imports
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+struct Import {
+    path: String,
+    // Byte range of the whole statement
+    start: usize,
+    end: usize,
+    has_alias: bool,
+}
+
+// Normalize a path relative to the file importing it
+pub fn resolve_path(from: &str, path: &str) -> String {
+    let mut parts: Vec<&str> = if path.starts_with('/') {
+        Vec::new()
+    } else {
+        from.split('/').collect()
+    };
+
+    // Remove the file name of the importer
+    parts.pop();
+    for part in path.split('/') {
+        match part {
+            "" | "." => {}
+            ".." => {
+                parts.pop();
+            }
+            part => parts.push(part),
+        }
+    }
+
+    parts.join("/")
+}
+
+fn normalize(path: &str) -> String {
+    resolve_path("", path)
+}
+
+fn imports(source: &str) -> Vec<Import> {
+    let tokens: Vec<_> = syntax::scan(source)
+        .into_iter()
+        .filter(|t| !t.is_trivia())
+        .collect();
+
+    let mut imports = Vec::new();
+    let mut depth = 0usize;
+    for (i, token) in tokens.iter().enumerate() {
+        match (token.kind, token.text) {
+            (TokenKind::Punctuation, "{") => depth += 1,
+            (TokenKind::Punctuation, "}") => depth = depth.saturating_sub(1),
+            (TokenKind::Keyword, "import") if depth == 0 => {
+                let Some(path) = tokens.get(i + 1).filter(|t| t.kind == TokenKind::String) else {
+                    continue;
+                };
+
+                let end = tokens[i + 1..]
+                    .iter()
+                    .find(|t| t.text == ";" || (t.kind == TokenKind::Keyword && t.text != "as"))
+                    .map(|t| if t.text == ";" { t.end } else { t.start })
+                    .unwrap_or(source.len());
+
+                imports.push(Import {
+                    path: path.text.trim_matches(|c| c == '"' || c == '\'').to_owned(),
+                    start: token.start,
+                    end,
+                    has_alias: tokens.get(i + 2).map(|t| t.text == "as").unwrap_or(false),
+                });
+            }
+            _ => {}
+        }
+    }
+
+    imports
+}
+
+struct Bundler<'a> {
+    files: &'a HashMap<String, String>,
+    visited: HashSet<String>,
+    bundle: Bundle,
+}
+
+impl<'a> Bundler<'a> {
+    fn add(&mut self, path: &str, stack: &mut Vec<String>) {
+        if self.visited.contains(path) {
+            return;
+        }
+
+        let files = self.files;
+        let Some(source) = files.get(path) else {
+            return;
+        };
+
+        self.visited.insert(path.to_owned());
+        stack.push(path.to_owned());
+
+        let index = LineIndex::new(source);
+        let mut code = source.clone();
+        for import in imports(source) {
+            let span = Some(index.span(import.start, import.end));
+            let target = resolve_path(path, &import.path);
+
+            let error = if import.has_alias {
+                Some("Imports with an alias are not supported in projects".to_owned())
+            } else if !self.files.contains_key(&target) {
+                Some(format!("File `{}` not found in the project", target))
+            } else if stack.contains(&target) {
+                Some(format!("Circular import of `{}`", target))
+            } else {
+                None
+            };
+
+            match error {
+                Some(message) => self.bundle.diagnostics.push(
+                    Diagnostic::error(DiagnosticPhase::Parse, message, span).with_file(path)
+                ),
+                None => self.add(&target, stack),
+            }
+
+            // Imports are resolved here, the compiler must not see them
+            // Byte offsets are kept intact to locate the diagnostics
+            let blank: String = source[import.start..import.end]
+                .chars()
+                .flat_map(|c| {
+                    let (c, count) = if c == '\n' { ('\n', 1) } else { (' ', c.len_utf8()) };
+                    std::iter::repeat(c).take(count)
+                })
+                .collect();
+            code.replace_range(import.start..import.end, &blank);
+        }
+
+        stack.pop();
+
+        let start = self.bundle.source.len();
+        self.bundle.source.push_str(&code);
+        self.bundle.segments.push(Segment {
+            path: path.to_owned(),
+            start,
+            end: self.bundle.source.len(),
+        });
+        self.bundle.source.push('\n');
+    }
+}
+
+pub fn bundle(files: &HashMap<String, String>, entry: &str) -> Bundle {
+    // Paths are always compared in their normalized form
+    let files: HashMap<String, String> = files
+        .iter()
+        .map(|(path, source)| (normalize(path), source.clone()))
+        .collect();
+
+    let entry = normalize(entry);
+    let mut bundler = Bundler {
+        files: &files,
+        visited: HashSet::new(),
+        bundle: Bundle {
+            source: String::new(),
+            segments: Vec::new(),
+            diagnostics: Vec::new(),
+        },
+    };
+
+    if !files.contains_key(&entry) {
+        bundler.bundle.diagnostics.push(Diagnostic::error(
+            DiagnosticPhase::Parse,
+            format!("Entry file `{}` not found in the project", entry),
+            None,
+        ));
+    }
+
+    bundler.add(&entry, &mut Vec::new());
+
+    bundler.bundle
+}
+
+impl Bundle {
+    // Convert a diagnostic located in the bundled source to its original file
+    pub fn locate(&self, diagnostic: Diagnostic, files: &HashMap<String, String>) -> Diagnostic {
+        let Some(span) = diagnostic.span() else {
+            return diagnostic;
+        };
+
+        let Some(segment) = self.segments
+            .iter()
+            .find(|s| s.start <= span.start_offset() && span.start_offset() <= s.end) else {
+            return diagnostic;
+        };
+
+        let Some(source) = files
+            .iter()
+            .find(|(path, _)| normalize(path) == segment.path)
+            .map(|(_, source)| source) else {
+            return diagnostic;
+        };
+
+        let index = LineIndex::new(source);
+        let start = span.start_offset() - segment.start;
+        let end = span.end_offset().min(segment.end) - segment.start;
+        let path = segment.path.clone();
+
+        diagnostic
+            .with_span(Some(index.span(start, end.max(start))))
+            .with_file(&path)
+    }
+}
@@ -0,0 +1,171 @@
+// Rename a symbol declared in the source
+// All its occurrences are replaced, after checking the new name doesn't
+// collide with another symbol or a builtin of the environment.
+// Only the symbols whose every occurrence was matched with the parsed program can be renamed.
+
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use crate::{
+    abi::parse_type_name,
+    builtins::{type_matches, Environment},
+    span::{LineIndex, Span},
+    symbols::{SymbolIndex, SymbolKind, Target},
+    syntax::KEYWORDS,
+};
+
+#[wasm_bindgen]
+#[derive(Debug, Clone)]
+pub struct TextEdit {
+    span: Span,
+    new_text: String,
+}
+
+#[wasm_bindgen]
+impl TextEdit {
+    // Range of the text to replace
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
+    pub fn new_text(&self) -> String {
+        self.new_text.clone()
+    }
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    let valid_start = chars
+        .next()
+        .map(|c| c.is_ascii_alphabetic() || c == '_')
+        .unwrap_or(false);
+
+    valid_start && chars.all(|c| c.is_ascii_alphanumeric() || c == '_') && !KEYWORDS.contains(&name)
+}
+
+// Builtin of the environment the new name would be confused with
+fn builtin_collision(index: &SymbolIndex, id: usize, name: &str, env: &Environment) -> bool {
+    let symbol = &index.symbols[id];
+    match symbol.kind {
+        SymbolKind::Function if symbol.on_type.is_some() => {
+            let ty = symbol.on_type.as_deref().unwrap_or_default();
+            env.functions
+                .iter()
+                .any(|f| f.name == name && f.on_type.as_deref().map(|t| type_matches(t, ty)).unwrap_or(false))
+        }
+        SymbolKind::Function | SymbolKind::Entry | SymbolKind::Hook => env.functions
+            .iter()
+            .any(|f| f.name == name && f.on_type.is_none()),
+        SymbolKind::Struct | SymbolKind::Enum => env.opaque_types.contains(name) || parse_type_name(name, &|_| None).is_some(),
+        _ => false,
+    }
+}
+
+// Symbol declared with the same name where the renamed symbol is used
+fn symbol_collision(index: &SymbolIndex, id: usize, name: &str) -> Option<usize> {
+    let symbol = &index.symbols[id];
+    let collides = |other: usize| -> bool {
+        let candidate = &index.symbols[other];
+        if other == id || candidate.name != name {
+            return false;
+        }
+
+        match symbol.kind {
+            // Same struct, variant or enum
+            SymbolKind::Field | SymbolKind::Variant => candidate.parent == symbol.parent && candidate.kind == symbol.kind,
+            // Variables visible at the same time in the same function
+            SymbolKind::Local | SymbolKind::Parameter => {
+                let same_function = candidate.parent == symbol.parent
+                    && matches!(candidate.kind, SymbolKind::Local | SymbolKind::Parameter);
+                let overlaps = symbol.kind == SymbolKind::Parameter
+                    || candidate.kind == SymbolKind::Parameter
+                    || (candidate.visible_start < symbol.visible_end && symbol.visible_start < candidate.visible_end);
+                same_function && overlaps
+            }
+            SymbolKind::Function if symbol.on_type.is_some() => candidate.parent.is_none() && candidate.on_type == symbol.on_type,
+            _ => candidate.parent.is_none() && candidate.on_type.is_none(),
+        }
+    };
+
+    if let Some(other) = (0..index.symbols.len()).find(|other| collides(*other)) {
+        return Some(other);
+    }
+
+    // A variable would hide a declaration used in its scope
+    if matches!(symbol.kind, SymbolKind::Local | SymbolKind::Parameter) {
+        let (start, end) = match symbol.kind {
+            SymbolKind::Parameter => symbol.parent
+                .map(|f| (index.symbols[f].decl_start, index.symbols[f].decl_end))
+                .unwrap_or((symbol.visible_start, symbol.visible_end)),
+            _ => (symbol.visible_start, symbol.visible_end),
+        };
+
+        return index.occurrences
+            .iter()
+            .filter(|o| start <= o.start && o.end <= end)
+            .find_map(|o| match o.target {
+                Target::Symbol(other) if other != id && index.symbols[other].name == name => Some(other),
+                _ => None,
+            });
+    }
+
+    // A reference would be hidden by a variable visible where it's used
+    index.references_of(id).find_map(|o| {
+        (0..index.symbols.len()).find(|other| {
+            let candidate = &index.symbols[*other];
+            candidate.name == name && match candidate.kind {
+                SymbolKind::Local => candidate.visible_start <= o.start && o.start <= candidate.visible_end,
+                SymbolKind::Parameter => candidate.parent
+                    .map(|f| index.symbols[f].decl_start <= o.start && o.start <= index.symbols[f].decl_end)
+                    .unwrap_or(false),
+                _ => false,
+            }
+        })
+    })
+}
+
+pub fn rename(
+    index: &SymbolIndex,
+    lines: &LineIndex,
+    offset: usize,
+    new_name: &str,
+    env: &Environment,
+) -> Result<Vec<TextEdit>, String> {
+    let Some(Target::Symbol(id)) = index.occurrence_at(offset).map(|o| &o.target) else {
+        return Err("No symbol declared in the code at this position".to_owned());
+    };
+
+    let id = *id;
+    let symbol = &index.symbols[id];
+    if symbol.kind == SymbolKind::Import {
+        return Err("Imports can't be renamed".to_owned());
+    }
+
+    if !index.is_exact(id) {
+        return Err(format!("`{}` can't be renamed safely: some of its uses couldn't be resolved", symbol.name));
+    }
+
+    if !is_identifier(new_name) {
+        return Err(format!("`{}` is not a valid name", new_name));
+    }
+
+    if let Some(other) = symbol_collision(index, id, new_name) {
+        let other = &index.symbols[other];
+        let (line, column) = lines.position(other.start);
+        return Err(format!(
+            "`{}` is already used by a {} at line {}, column {}",
+            new_name, other.kind.as_str(), line, column
+        ));
+    }
+
+    if builtin_collision(index, id, new_name, env) {
+        return Err(format!("`{}` is already provided by the environment", new_name));
+    }
+
+    Ok(index
+        .occurrences_of(id)
+        .map(|o| TextEdit {
+            span: lines.span(o.start, o.end),
+            new_text: new_name.to_owned(),
+        })
+        .collect())
+}
@@ -0,0 +1,69 @@
+// Semantic classification of the identifiers, for editors coloring
+// the names by what they refer to instead of their spelling.
+
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use crate::{
+    builtins::Environment,
+    span::{LineIndex, Span},
+    symbols::{SymbolIndex, SymbolKind, Target},
+};
+
+#[wasm_bindgen]
+#[derive(Debug, Clone)]
+pub struct SemanticToken {
+    // local, parameter, function, entry, hook, struct, enum, variant, field, constant,
+    // import, builtin_function, builtin_type or opaque_type
+    token_type: &'static str,
+    declaration: bool,
+    span: Span,
+}
+
+#[wasm_bindgen]
+impl SemanticToken {
+    pub fn token_type(&self) -> String {
+        self.token_type.to_owned()
+    }
+
+    pub fn is_declaration(&self) -> bool {
+        self.declaration
+    }
+
+    pub fn span(&self) -> Span {
+        self.span
+    }
+}
+
+fn symbol_type(kind: SymbolKind) -> &'static str {
+    match kind {
+        SymbolKind::Local => "local",
+        kind => kind.as_str(),
+    }
+}
+
+pub fn build(index: &SymbolIndex, lines: &LineIndex, env: &Environment) -> Vec<SemanticToken> {
+    let mut previous = None;
+    index.occurrences
+        .iter()
+        // A field initialized by a variable of the same name is classified as the field
+        .filter(|occurrence| previous.replace(occurrence.start) != Some(occurrence.start))
+        .filter_map(|occurrence| {
+            let token_type = match &occurrence.target {
+                Target::Symbol(id) => symbol_type(index.symbols[*id].kind),
+                target @ (Target::Function { .. } | Target::Method { .. }) => {
+                    env.function(target)?;
+                    "builtin_function"
+                }
+                Target::Type(name) if env.opaque_types.contains(name) => "opaque_type",
+                Target::Type(_) => "builtin_type",
+                Target::Unknown => return None,
+            };
+
+            Some(SemanticToken {
+                token_type,
+                declaration: occurrence.declaration,
+                span: lines.span(occurrence.start, occurrence.end),
+            })
+        })
+        .collect()
+}
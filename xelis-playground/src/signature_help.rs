@@ -0,0 +1,120 @@
+// Parameters of the call surrounding the cursor
+
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use crate::{
+    builtins::Environment,
+    symbols::{SymbolIndex, Target},
+    syntax::{self, SyntaxToken},
+};
+
+#[wasm_bindgen]
+#[derive(Debug, Clone)]
+pub struct SignatureHelp {
+    name: String,
+    signature: String,
+    doc: Option<String>,
+    // Written as `name: type`
+    parameters: Vec<String>,
+    active_parameter: usize,
+}
+
+#[wasm_bindgen]
+impl SignatureHelp {
+    pub fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    pub fn signature(&self) -> String {
+        self.signature.clone()
+    }
+
+    pub fn doc(&self) -> Option<String> {
+        self.doc.clone()
+    }
+
+    pub fn parameters(&self) -> Vec<String> {
+        self.parameters.clone()
+    }
+
+    // Index of the argument being written
+    pub fn active_parameter(&self) -> usize {
+        self.active_parameter
+    }
+
+    // Expected type of the argument being written, none if there are too many arguments
+    pub fn active_type(&self) -> Option<String> {
+        self.parameters
+            .get(self.active_parameter)
+            .and_then(|p| p.split_once(':'))
+            .map(|(_, ty)| ty.trim().to_owned())
+    }
+}
+
+// Opening parenthesis of the call containing the end of the tokens and the argument index
+fn enclosing_call(tokens: &[SyntaxToken]) -> Option<(usize, usize)> {
+    let mut depth = 0usize;
+    let mut argument = 0;
+
+    for (i, token) in tokens.iter().enumerate().rev() {
+        match token.text {
+            ")" | "]" | "}" => depth += 1,
+            "(" if depth == 0 => return Some((i, argument)),
+            "[" | "{" if depth == 0 => return None,
+            "(" | "[" | "{" => depth -= 1,
+            "," if depth == 0 => argument += 1,
+            ";" if depth == 0 => return None,
+            _ => {}
+        }
+    }
+
+    None
+}
+
+pub fn signature_help(index: &SymbolIndex, source: &str, offset: usize, env: &Environment) -> Option<SignatureHelp> {
+    let mut offset = offset.min(source.len());
+    // The offset may fall inside a multi-bytes character
+    while !source.is_char_boundary(offset) {
+        offset -= 1;
+    }
+    let tokens: Vec<SyntaxToken> = syntax::scan(&source[..offset])
+        .into_iter()
+        .filter(|t| !t.is_trivia())
+        .collect();
+
+    let (open, active_parameter) = enclosing_call(&tokens)?;
+    let callee = tokens.get(open.checked_sub(1)?)?;
+    let occurrence = index.occurrences.iter().find(|o| o.start == callee.start)?;
+
+    let help = match &occurrence.target {
+        Target::Symbol(id) if index.symbols[*id].kind.is_callable() => {
+            let symbol = &index.symbols[*id];
+            SignatureHelp {
+                name: symbol.name.clone(),
+                signature: index.signature(*id),
+                doc: symbol.doc.clone(),
+                parameters: symbol.children
+                    .iter()
+                    .map(|p| {
+                        let parameter = &index.symbols[*p];
+                        format!("{}: {}", parameter.name, parameter.ty.as_deref().unwrap_or("any"))
+                    })
+                    .collect(),
+                active_parameter,
+            }
+        }
+        target @ (Target::Function { .. } | Target::Method { .. }) => {
+            let function = env.function(target)?;
+            SignatureHelp {
+                name: function.name.clone(),
+                signature: function.signature(),
+                doc: function.comment.clone(),
+                parameters: function.params.clone(),
+                active_parameter,
+            }
+        }
+        _ => return None,
+    };
+
+    Some(help)
+}
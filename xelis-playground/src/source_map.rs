@@ -0,0 +1,111 @@
+// Link the calls of the compiled chunks back to their call sites
+// The compiler doesn't emit debug information, so this is not a map of every
+// instruction: only the syscalls and chunk calls are located, by matching them
+// in order with the calls written in the function that produced the chunk.
+
+use std::collections::HashMap;
+
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use crate::{
+    listing::ChunkListing,
+    span::{LineIndex, Span},
+    symbols::{SymbolIndex, Target},
+};
+
+#[wasm_bindgen]
+#[derive(Debug, Clone)]
+pub struct SourceMapEntry {
+    chunk_id: usize,
+    // Range of instruction indexes of the call, end excluded
+    instruction_start: usize,
+    instruction_end: usize,
+    span: Span,
+}
+
+#[wasm_bindgen]
+impl SourceMapEntry {
+    pub fn chunk_id(&self) -> usize {
+        self.chunk_id
+    }
+
+    pub fn instruction_start(&self) -> usize {
+        self.instruction_start
+    }
+
+    pub fn instruction_end(&self) -> usize {
+        self.instruction_end
+    }
+
+    pub fn span(&self) -> Span {
+        self.span
+    }
+}
+
+impl SourceMapEntry {
+    pub fn contains(&self, chunk_id: usize, instruction: usize) -> bool {
+        self.chunk_id == chunk_id && self.instruction_start <= instruction && instruction < self.instruction_end
+    }
+}
+
+// The chunks are compiled in the order of the functions of the program
+pub fn build(
+    listing: &[ChunkListing],
+    index: &SymbolIndex,
+    lines: &LineIndex,
+    syscall_names: &HashMap<u16, String>,
+) -> Vec<SourceMapEntry> {
+    let functions = &index.functions;
+    let mut entries = Vec::new();
+
+    for chunk in listing {
+        let Some(function) = functions.get(chunk.id).copied().flatten().map(|id| &index.symbols[id]) else {
+            continue;
+        };
+
+        let calls: Vec<_> = index.occurrences
+            .iter()
+            .filter(|o| !o.declaration && function.decl_start < o.start && o.end < function.decl_end)
+            .filter(|o| match &o.target {
+                Target::Function { .. } | Target::Method { .. } => true,
+                Target::Symbol(id) => index.symbols[*id].kind.is_callable(),
+                _ => false,
+            })
+            .collect();
+
+        let mut cursor = 0;
+        for (i, instruction) in chunk.instructions.iter().enumerate() {
+            let matches = |target: &Target| -> bool {
+                if instruction.is_syscall() {
+                    let Some(expected) = instruction.operand(0).and_then(|id| syscall_names.get(&(id as u16))) else {
+                        return false;
+                    };
+                    match target {
+                        Target::Function { name, .. } | Target::Method { name, .. } => expected == name,
+                        _ => false,
+                    }
+                } else if instruction.is_chunk_call() {
+                    let Some(expected) = instruction.operand(0).and_then(|id| functions.get(id as usize).copied().flatten()) else {
+                        return false;
+                    };
+                    matches!(target, Target::Symbol(id) if *id == expected)
+                } else {
+                    false
+                }
+            };
+
+            if let Some(position) = calls[cursor..].iter().position(|o| matches(&o.target)) {
+                let call = calls[cursor + position];
+                entries.push(SourceMapEntry {
+                    chunk_id: chunk.id,
+                    instruction_start: i,
+                    instruction_end: i + 1,
+                    span: lines.span(call.start, call.end),
+                });
+                cursor += position + 1;
+            }
+        }
+    }
+
+    entries
+}
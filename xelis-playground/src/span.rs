@@ -0,0 +1,138 @@
+use wasm_bindgen::prelude::wasm_bindgen;
+
+// A range in the source code
+// Lines and columns are 1-based and counted in characters,
+// offsets are 0-based byte offsets into the UTF-8 source
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Span {
+    start_line: usize,
+    start_column: usize,
+    end_line: usize,
+    end_column: usize,
+    start_offset: usize,
+    end_offset: usize,
+}
+
+#[wasm_bindgen]
+impl Span {
+    pub fn start_line(&self) -> usize {
+        self.start_line
+    }
+
+    pub fn start_column(&self) -> usize {
+        self.start_column
+    }
+
+    pub fn end_line(&self) -> usize {
+        self.end_line
+    }
+
+    pub fn end_column(&self) -> usize {
+        self.end_column
+    }
+
+    pub fn start_offset(&self) -> usize {
+        self.start_offset
+    }
+
+    pub fn end_offset(&self) -> usize {
+        self.end_offset
+    }
+}
+
+impl Span {
+    // Check if a byte offset is inside the span (end inclusive, for cursors)
+    pub fn contains(&self, offset: usize) -> bool {
+        self.start_offset <= offset && offset <= self.end_offset
+    }
+}
+
+// Index of the line starts of a source code
+// Used to convert between byte offsets and line/column positions
+pub struct LineIndex<'a> {
+    source: &'a str,
+    line_starts: Vec<usize>,
+}
+
+impl<'a> LineIndex<'a> {
+    pub fn new(source: &'a str) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(source.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+
+        Self {
+            source,
+            line_starts,
+        }
+    }
+
+    pub fn source(&self) -> &'a str {
+        self.source
+    }
+
+    // Byte range of a 1-based line, without its line ending
+    fn line_range(&self, line: usize) -> (usize, usize) {
+        let line = line.clamp(1, self.line_starts.len());
+        let start = self.line_starts[line - 1];
+        let end = self.line_starts
+            .get(line)
+            .map(|next| next - 1)
+            .unwrap_or(self.source.len());
+
+        let end = if self.source[start..end].ends_with('\r') { end - 1 } else { end };
+        (start, end)
+    }
+
+    // Byte offset of a 1-based line and column
+    // The column is clamped to the end of the line
+    pub fn offset(&self, line: usize, column: usize) -> usize {
+        let (start, end) = self.line_range(line);
+        self.source[start..end]
+            .char_indices()
+            .nth(column.saturating_sub(1))
+            .map(|(i, _)| start + i)
+            .unwrap_or(end)
+    }
+
+    // 1-based line and column of a byte offset
+    pub fn position(&self, offset: usize) -> (usize, usize) {
+        let mut offset = offset.min(self.source.len());
+        // The offset may fall inside a multi-bytes character
+        while !self.source.is_char_boundary(offset) {
+            offset -= 1;
+        }
+        let line = self.line_starts.partition_point(|start| *start <= offset);
+        let start = self.line_starts[line - 1];
+        let column = self.source[start..offset].chars().count() + 1;
+
+        (line, column)
+    }
+
+    pub fn span(&self, start_offset: usize, end_offset: usize) -> Span {
+        let end_offset = end_offset.max(start_offset);
+        let (start_line, start_column) = self.position(start_offset);
+        let (end_line, end_column) = self.position(end_offset);
+
+        Span {
+            start_line,
+            start_column,
+            end_line,
+            end_column,
+            start_offset: start_offset.min(self.source.len()),
+            end_offset: end_offset.min(self.source.len()),
+        }
+    }
+
+    // Span going from a line/column position to another one on the same line
+    pub fn columns_span(&self, line: usize, column_start: usize, column_end: usize) -> Span {
+        self.span(self.offset(line, column_start), self.offset(line, column_end))
+    }
+
+    // Span going from an offset up to the end of its line
+    pub fn span_to_line_end(&self, offset: usize) -> Span {
+        let (line, _) = self.position(offset);
+        let (_, end) = self.line_range(line);
+        self.span(offset, end)
+    }
+}
@@ -0,0 +1,150 @@
+// Statistics of a compiled module
+// Instructions are counted from the opcodes decoded from the chunks
+
+use std::collections::BTreeSet;
+
+use indexmap::IndexMap;
+use wasm_bindgen::prelude::wasm_bindgen;
+use xelis_bytecode::Module;
+
+use crate::listing::ChunkListing;
+
+#[wasm_bindgen]
+#[derive(Debug, Clone)]
+pub struct ChunkStats {
+    chunk_id: usize,
+    instruction_count: usize,
+}
+
+#[wasm_bindgen]
+impl ChunkStats {
+    pub fn chunk_id(&self) -> usize {
+        self.chunk_id
+    }
+
+    pub fn instruction_count(&self) -> usize {
+        self.instruction_count
+    }
+}
+
+#[wasm_bindgen]
+#[derive(Debug, Clone)]
+pub struct OpcodeCount {
+    opcode: String,
+    count: usize,
+}
+
+#[wasm_bindgen]
+impl OpcodeCount {
+    pub fn opcode(&self) -> String {
+        self.opcode.clone()
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+}
+
+// Size report of a compiled module
+#[wasm_bindgen]
+#[derive(Debug, Clone)]
+pub struct ModuleStats {
+    chunks: Vec<ChunkStats>,
+    constants_count: usize,
+    // Sorted by decreasing count
+    opcodes: Vec<OpcodeCount>,
+    syscall_ids: Vec<u16>,
+    structs_count: usize,
+    enums_count: usize,
+    byte_size: usize,
+}
+
+#[wasm_bindgen]
+impl ModuleStats {
+    pub fn chunk_count(&self) -> usize {
+        self.chunks.len()
+    }
+
+    pub fn chunks(&self) -> Vec<ChunkStats> {
+        self.chunks.clone()
+    }
+
+    pub fn instruction_count(&self) -> usize {
+        self.chunks
+            .iter()
+            .map(|c| c.instruction_count)
+            .sum()
+    }
+
+    pub fn constants_count(&self) -> usize {
+        self.constants_count
+    }
+
+    pub fn opcodes(&self) -> Vec<OpcodeCount> {
+        self.opcodes.clone()
+    }
+
+    // Distinct syscalls referenced by the module
+    pub fn syscall_ids(&self) -> Vec<u16> {
+        self.syscall_ids.clone()
+    }
+
+    pub fn syscall_count(&self) -> usize {
+        self.syscall_ids.len()
+    }
+
+    pub fn structs_count(&self) -> usize {
+        self.structs_count
+    }
+
+    pub fn enums_count(&self) -> usize {
+        self.enums_count
+    }
+
+    // Size of the module serialized with `Module::to_bytes`
+    pub fn byte_size(&self) -> usize {
+        self.byte_size
+    }
+}
+
+impl ModuleStats {
+    pub fn new(module: &Module, listing: &[ChunkListing], byte_size: usize) -> Self {
+        let mut opcodes: IndexMap<String, usize> = IndexMap::new();
+        let mut syscall_ids = BTreeSet::new();
+
+        for instruction in listing.iter().flat_map(|c| c.instructions.iter()) {
+            *opcodes.entry(instruction.mnemonic()).or_default() += 1;
+            if instruction.is_syscall() {
+                syscall_ids.extend(instruction.operand(0).map(|id| id as u16));
+            }
+        }
+
+        let mut opcodes: Vec<OpcodeCount> = opcodes
+            .into_iter()
+            .map(|(opcode, count)| OpcodeCount {
+                opcode,
+                count,
+            })
+            .collect();
+        opcodes.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.opcode.cmp(&b.opcode)));
+
+        Self {
+            chunks: (0..module.chunks().len())
+                .map(|chunk_id| ChunkStats {
+                    chunk_id,
+                    instruction_count: listing
+                        .iter()
+                        .find(|c| c.id == chunk_id)
+                        .map(|c| c.instructions.len())
+                        .unwrap_or(0),
+                })
+                .collect(),
+            constants_count: module.constants().len(),
+            opcodes,
+            syscall_ids: syscall_ids.into_iter().collect(),
+            structs_count: module.structs().len(),
+            enums_count: module.enums().len(),
+            byte_size,
+        }
+    }
+}
@@ -0,0 +1,1491 @@
+// Index of the symbols declared and referenced in a Silex source
+// Scopes, references and types are the ones resolved by the parser. The program it builds
+// keeps no positions, so the lexer tokens are matched with it in source order to locate them:
+// the functions in which an identifier can't be matched are kept as unresolved.
+
+use std::collections::HashMap;
+
+use xelis_ast::{Expression, IdentifierType, Program as AstProgram, Statement};
+use xelis_parser::mapper::GlobalMapper;
+use xelis_types::Type;
+
+use crate::{
+    abi::parse_type_name,
+    syntax::{self, SyntaxToken, TokenKind},
+    warnings,
+};
+
+// Program built by the parser from the indexed source
+pub struct Parsed<'p, 'a> {
+    pub program: &'p AstProgram,
+    pub mapper: &'p GlobalMapper<'a>,
+    // The functions of the environment come first in the mapper
+    pub env_offset: u16,
+    // Opaque type of the environment by name
+    pub opaque: &'p dyn Fn(&str) -> Option<Type>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolKind {
+    Function,
+    Entry,
+    Hook,
+    Struct,
+    Enum,
+    Variant,
+    Field,
+    Constant,
+    Import,
+    Parameter,
+    Local,
+}
+
+impl SymbolKind {
+    pub fn is_callable(&self) -> bool {
+        matches!(self, Self::Function | Self::Entry | Self::Hook)
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Function => "function",
+            Self::Entry => "entry",
+            Self::Hook => "hook",
+            Self::Struct => "struct",
+            Self::Enum => "enum",
+            Self::Variant => "variant",
+            Self::Field => "field",
+            Self::Constant => "constant",
+            Self::Import => "import",
+            Self::Parameter => "parameter",
+            Self::Local => "variable",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Symbol {
+    pub name: String,
+    pub kind: SymbolKind,
+    // Byte range of the name
+    pub start: usize,
+    pub end: usize,
+    // Byte range of the whole declaration
+    pub decl_start: usize,
+    pub decl_end: usize,
+    // Struct of a field, enum of a variant, function of a variable
+    pub parent: Option<usize>,
+    // Parameters of a function, fields of a struct or variant, variants of an enum
+    pub children: Vec<usize>,
+    // Type as displayed, or the return type for the functions
+    pub ty: Option<String>,
+    // Type resolved by the parser
+    pub resolved: Option<Type>,
+    // Type on which a function is declared
+    pub on_type: Option<String>,
+    // Value of a constant as written in the source
+    pub value: Option<String>,
+    pub doc: Option<String>,
+    // Byte range in which a variable can be referenced
+    pub visible_start: usize,
+    pub visible_end: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Target {
+    // Symbol declared in the source
+    Symbol(usize),
+    // Function provided by the environment
+    Function {
+        name: String,
+        on_type: Option<String>,
+    },
+    // Method called on a value whose type is not declared in the source
+    Method {
+        name: String,
+        receiver: Option<String>,
+    },
+    // Type provided by the language or the environment
+    Type(String),
+    Unknown,
+}
+
+// An identifier found in the source and what it refers to
+#[derive(Debug, Clone)]
+pub struct Occurrence {
+    pub start: usize,
+    pub end: usize,
+    pub target: Target,
+    pub declaration: bool,
+}
+
+#[derive(Debug, Default)]
+pub struct SymbolIndex {
+    pub symbols: Vec<Symbol>,
+    // Sorted by position
+    pub occurrences: Vec<Occurrence>,
+    // Local shadowing another visible variable
+    pub shadows: Vec<(usize, usize)>,
+    // Byte ranges of statements placed after a return, break or continue
+    pub unreachable: Vec<(usize, usize)>,
+    // Symbol of each function of the program, in the order of the chunks
+    pub functions: Vec<Option<usize>>,
+    // Byte ranges of the expressions and the types resolved for them
+    pub expressions: Vec<(usize, usize, Type)>,
+    // Byte ranges in which identifiers couldn't be matched with the program
+    pub unresolved: Vec<(usize, usize)>,
+    // Names standing for several symbols, such as a field initialized by the variable of the same name
+    pub ambiguous: Vec<(usize, usize)>,
+    // Code failing to parse was removed before indexing
+    pub recovered: bool,
+}
+
+impl SymbolIndex {
+    pub fn build(source: &str, parsed: &Parsed) -> Self {
+        let all = syntax::scan(source);
+        let tokens: Vec<SyntaxToken> = all
+            .iter()
+            .copied()
+            .filter(|t| !t.is_trivia())
+            .collect();
+
+        let mut builder = Builder {
+            source,
+            all: &all,
+            matching: matching_delimiters(&tokens),
+            claimed: vec![false; tokens.len()],
+            tokens,
+            parsed,
+            index: SymbolIndex {
+                functions: vec![None; parsed.program.functions().len()],
+                ..Default::default()
+            },
+            top_level: HashMap::new(),
+            bodies: Vec::new(),
+        };
+
+        builder.declarations();
+        for body in std::mem::take(&mut builder.bodies) {
+            builder.body(body);
+        }
+        builder.names();
+
+        let mut index = builder.index;
+        index.occurrences.sort_by_key(|o| o.start);
+        index
+    }
+
+    // Whether every occurrence of the symbol is known, so it can be renamed safely
+    // Variables only depend on their function, the other symbols on the whole code
+    pub fn is_exact(&self, id: usize) -> bool {
+        let ambiguous = self.occurrences_of(id)
+            .any(|o| self.ambiguous.contains(&(o.start, o.end)));
+        if self.recovered || ambiguous {
+            return false;
+        }
+
+        let symbol = &self.symbols[id];
+        match symbol.kind {
+            SymbolKind::Local | SymbolKind::Parameter => {
+                let Some(function) = symbol.parent.map(|f| &self.symbols[f]) else {
+                    return false;
+                };
+                !self.unresolved
+                    .iter()
+                    .any(|(start, end)| *start < function.decl_end && function.decl_start < *end)
+            }
+            _ => self.unresolved.is_empty(),
+        }
+    }
+
+    // Innermost expression containing the offset with the type resolved by the parser
+    pub fn expression_at(&self, offset: usize) -> Option<&(usize, usize, Type)> {
+        self.expressions
+            .iter()
+            .filter(|(start, end, _)| *start <= offset && offset <= *end)
+            .min_by_key(|(start, end, _)| end - start)
+    }
+
+    // Occurrence under the cursor, its end included so it works right after typing a name
+    pub fn occurrence_at(&self, offset: usize) -> Option<&Occurrence> {
+        let position = self.occurrences.partition_point(|o| o.end < offset);
+        self.occurrences[position..]
+            .iter()
+            .take_while(|o| o.start <= offset)
+            .last()
+    }
+
+    // All the occurrences of a symbol, declaration included
+    pub fn occurrences_of(&self, id: usize) -> impl Iterator<Item = &Occurrence> {
+        self.occurrences
+            .iter()
+            .filter(move |o| o.target == Target::Symbol(id))
+    }
+
+    pub fn references_of(&self, id: usize) -> impl Iterator<Item = &Occurrence> {
+        self.occurrences_of(id).filter(|o| !o.declaration)
+    }
+
+    pub fn is_referenced(&self, id: usize) -> bool {
+        self.references_of(id).next().is_some()
+    }
+
+    // Declarations that are not nested in another one
+    pub fn top_level(&self) -> impl Iterator<Item = (usize, &Symbol)> {
+        self.symbols
+            .iter()
+            .enumerate()
+            .filter(|(_, s)| s.parent.is_none())
+    }
+
+    pub fn find_top_level(&self, name: &str) -> Option<usize> {
+        self.top_level()
+            .find(|(_, s)| s.name == name && s.kind != SymbolKind::Import)
+            .map(|(id, _)| id)
+    }
+
+    // Resolve the fields available on a type written in the source
+    pub fn fields_of_type(&self, ty: &str) -> Option<&[usize]> {
+        let id = self.find_top_level(base_type(ty))?;
+        let symbol = &self.symbols[id];
+        (symbol.kind == SymbolKind::Struct).then_some(symbol.children.as_slice())
+    }
+
+    // Declaration of a symbol as it would be written in the source, without its body
+    pub fn signature(&self, id: usize) -> String {
+        let symbol = &self.symbols[id];
+        let typed = |id: &usize| {
+            let child = &self.symbols[*id];
+            match &child.ty {
+                Some(ty) => format!("{}: {}", child.name, ty),
+                None => child.name.clone(),
+            }
+        };
+
+        match symbol.kind {
+            SymbolKind::Function | SymbolKind::Entry | SymbolKind::Hook => {
+                let keyword = match symbol.kind {
+                    SymbolKind::Entry => "entry",
+                    SymbolKind::Hook => "hook",
+                    _ => "fn",
+                };
+
+                let receiver = symbol.on_type
+                    .as_ref()
+                    .map(|ty| format!(" (self {})", ty))
+                    .unwrap_or_default();
+                let params: Vec<String> = symbol.children.iter().map(typed).collect();
+                // Entries always return an exit code, it is not written
+                let returns = symbol.ty
+                    .as_ref()
+                    .filter(|_| symbol.kind != SymbolKind::Entry)
+                    .map(|ty| format!(" -> {}", ty))
+                    .unwrap_or_default();
+
+                format!("{}{} {}({}){}", keyword, receiver, symbol.name, params.join(", "), returns)
+            }
+            SymbolKind::Struct => {
+                let fields: Vec<String> = symbol.children.iter().map(typed).collect();
+                format!("struct {} {{ {} }}", symbol.name, fields.join(", "))
+            }
+            SymbolKind::Enum => {
+                let variants: Vec<String> = symbol.children
+                    .iter()
+                    .map(|id| self.signature(*id))
+                    .collect();
+                format!("enum {} {{ {} }}", symbol.name, variants.join(", "))
+            }
+            SymbolKind::Variant if !symbol.children.is_empty() => {
+                let fields: Vec<String> = symbol.children.iter().map(typed).collect();
+                format!("{} {{ {} }}", symbol.name, fields.join(", "))
+            }
+            SymbolKind::Variant => symbol.name.clone(),
+            SymbolKind::Constant => {
+                let mut signature = format!("const {}", typed(&id));
+                if let Some(value) = &symbol.value {
+                    signature.push_str(" = ");
+                    signature.push_str(value);
+                }
+                signature
+            }
+            SymbolKind::Import => format!("import {}", symbol.name),
+            SymbolKind::Field | SymbolKind::Parameter => typed(&id),
+            SymbolKind::Local => format!("let {}", typed(&id)),
+        }
+    }
+
+    // User functions declared on a type
+    pub fn methods_of_type<'a>(&'a self, ty: &'a str) -> impl Iterator<Item = usize> + 'a {
+        self.top_level()
+            .filter(move |(_, s)| s.on_type.as_deref() == Some(base_type(ty)))
+            .map(|(id, _)| id)
+    }
+}
+
+// Remove the optional and array decorations of a type
+pub fn base_type(ty: &str) -> &str {
+    let ty = ty.trim();
+    let ty = ty
+        .strip_prefix("optional<")
+        .and_then(|t| t.strip_suffix('>'))
+        .unwrap_or(ty);
+
+    ty.trim_end_matches("[]").trim()
+}
+
+// Type of the elements of an array type
+pub fn element_type(ty: &str) -> Option<&str> {
+    ty.trim().strip_suffix("[]")
+}
+
+fn matching_delimiters(tokens: &[SyntaxToken]) -> Vec<Option<usize>> {
+    let mut matching = vec![None; tokens.len()];
+    let mut stack: Vec<(usize, &str)> = Vec::new();
+
+    for (i, token) in tokens.iter().enumerate() {
+        if token.kind != TokenKind::Punctuation {
+            continue;
+        }
+
+        match token.text {
+            "(" | "[" | "{" => stack.push((i, token.text)),
+            ")" | "]" | "}" => {
+                let open = match token.text {
+                    ")" => "(",
+                    "]" => "[",
+                    _ => "{",
+                };
+
+                // Unbalanced code: drop the unclosed delimiters
+                if let Some(pos) = stack.iter().rposition(|(_, text)| *text == open) {
+                    let (j, _) = stack[pos];
+                    stack.truncate(pos);
+                    matching[i] = Some(j);
+                    matching[j] = Some(i);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    matching
+}
+
+// Body of a declared function, matched once all the declarations are known
+struct Body {
+    symbol: usize,
+    // Position of the function in the program
+    position: usize,
+    // Tokens opening and closing the body
+    open: usize,
+    close: usize,
+    // Symbol of each parameter by variable id
+    parameters: Vec<(IdentifierType, usize)>,
+}
+
+// Progress of the matching of a body with its statements
+struct Walk {
+    function: usize,
+    // Next token that can be matched
+    cursor: usize,
+    // Token ending the range being matched
+    end: usize,
+    // Symbol of each variable id in scope, the innermost last
+    bindings: Vec<(IdentifierType, usize)>,
+    failed: bool,
+}
+
+impl Walk {
+    fn binding(&self, id: IdentifierType) -> Option<usize> {
+        self.bindings
+            .iter()
+            .rev()
+            .find(|(variable, _)| *variable == id)
+            .map(|(_, symbol)| *symbol)
+    }
+}
+
+struct Builder<'t, 's, 'p, 'a> {
+    source: &'s str,
+    all: &'t [SyntaxToken<'s>],
+    tokens: Vec<SyntaxToken<'s>>,
+    matching: Vec<Option<usize>>,
+    // Tokens located in the program
+    claimed: Vec<bool>,
+    parsed: &'p Parsed<'p, 'a>,
+    index: SymbolIndex,
+    top_level: HashMap<String, usize>,
+    bodies: Vec<Body>,
+}
+
+impl<'t, 's, 'p, 'a> Builder<'t, 's, 'p, 'a> {
+    fn token(&self, i: usize) -> Option<&SyntaxToken<'s>> {
+        self.tokens.get(i)
+    }
+
+    fn is(&self, i: usize, text: &str) -> bool {
+        self.token(i)
+            .map(|t| t.text == text && !matches!(t.kind, TokenKind::String | TokenKind::Comment))
+            .unwrap_or(false)
+    }
+
+    fn is_ident(&self, i: usize) -> bool {
+        self.token(i)
+            .map(|t| t.kind == TokenKind::Identifier || t.text == "self")
+            .unwrap_or(false)
+    }
+
+    fn closing(&self, i: usize) -> Option<usize> {
+        self.matching.get(i).copied().flatten()
+    }
+
+    // Token starting at a byte offset
+    fn token_at(&self, offset: usize) -> Option<usize> {
+        self.tokens
+            .binary_search_by_key(&offset, |t| t.start)
+            .ok()
+    }
+
+    // Token closing the innermost block containing the token
+    fn enclosing_close(&self, token: usize) -> Option<usize> {
+        (0..token)
+            .rev()
+            .filter(|i| self.is(*i, "{"))
+            .find_map(|i| self.closing(i).filter(|close| *close > token))
+    }
+
+    // Source text between two tokens (inclusive) with normalized whitespaces
+    fn text(&self, from: usize, to: usize) -> String {
+        if from > to || to >= self.tokens.len() {
+            return String::new();
+        }
+
+        self.source[self.tokens[from].start..self.tokens[to].end]
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    // Comments placed right before a declaration
+    fn doc_before(&self, offset: usize) -> Option<String> {
+        let position = self.all.partition_point(|t| t.end <= offset);
+        let lines: Vec<String> = self.all[..position]
+            .iter()
+            .rev()
+            .take_while(|t| t.is_trivia())
+            .map(|t| {
+                let text = t.text;
+                let text = text
+                    .strip_prefix("/*")
+                    .and_then(|t| t.strip_suffix("*/"))
+                    .unwrap_or(text);
+
+                text.trim_start_matches('/')
+                    .lines()
+                    .map(|line| line.trim().trim_start_matches('*').trim())
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            })
+            .collect();
+
+        if lines.is_empty() {
+            return None;
+        }
+
+        Some(lines.into_iter().rev().collect::<Vec<_>>().join("\n").trim().to_owned())
+    }
+
+    // Struct, enum or opaque type named in the source
+    fn named_type(&self, name: &str) -> Option<Type> {
+        let program = self.parsed.program;
+        program.structures()
+            .iter()
+            .find(|ty| ty.name() == name)
+            .map(|ty| Type::Struct(ty.clone()))
+            .or_else(|| program.enums()
+                .iter()
+                .find(|ty| ty.name() == name)
+                .map(|ty| Type::Enum(ty.clone())))
+            .or_else(|| (self.parsed.opaque)(name))
+    }
+
+    fn unresolved(&mut self, range: (usize, usize)) {
+        self.index.unresolved.push(range);
+    }
+
+    fn declare(&mut self, name_token: usize, kind: SymbolKind, decl: (usize, usize), parent: Option<usize>) -> usize {
+        let token = self.tokens[name_token];
+        let id = self.index.symbols.len();
+        self.index.symbols.push(Symbol {
+            name: token.text.to_owned(),
+            kind,
+            start: token.start,
+            end: token.end,
+            decl_start: decl.0,
+            decl_end: decl.1,
+            parent,
+            children: Vec::new(),
+            ty: None,
+            resolved: None,
+            on_type: None,
+            value: None,
+            doc: None,
+            visible_start: token.end,
+            visible_end: decl.1,
+        });
+
+        if let Some(parent) = parent {
+            self.index.symbols[parent].children.push(id);
+        }
+
+        self.claimed[name_token] = true;
+        self.index.occurrences.push(Occurrence {
+            start: token.start,
+            end: token.end,
+            target: Target::Symbol(id),
+            declaration: true,
+        });
+
+        id
+    }
+
+    fn push_occurrence(&mut self, i: usize, target: Target) {
+        let token = self.tokens[i];
+        self.index.occurrences.push(Occurrence {
+            start: token.start,
+            end: token.end,
+            target,
+            declaration: false,
+        });
+    }
+
+    // Split the tokens between two delimiters on the commas at depth zero
+    fn split_commas(&self, open: usize, close: usize) -> Vec<(usize, usize)> {
+        let mut parts = Vec::new();
+        let mut start = open + 1;
+        let mut depth = 0i32;
+
+        for i in open + 1..close {
+            let token = &self.tokens[i];
+            match token.text {
+                "(" | "[" | "{" | "<" => depth += 1,
+                ")" | "]" | "}" | ">" => depth -= 1,
+                "," if depth <= 0 => {
+                    if start < i {
+                        parts.push((start, i - 1));
+                    }
+                    start = i + 1;
+                }
+                _ => {}
+            }
+        }
+
+        if start < close {
+            parts.push((start, close - 1));
+        }
+
+        parts
+    }
+
+    // Index of the token ending the statement starting at `from`
+    fn statement_end(&self, from: usize, limit: usize) -> usize {
+        let mut i = from;
+        while i < limit {
+            if self.is(i, ";") {
+                return i;
+            }
+
+            i = match self.token(i).map(|t| t.text) {
+                Some("(" | "[" | "{") => self.closing(i).map(|c| c + 1).unwrap_or(i + 1),
+                _ => i + 1,
+            };
+        }
+
+        limit
+    }
+
+    // Declare the `name: Type` parts between two delimiters
+    // Their types are the ones resolved by the parser when they are known
+    fn typed_names(&mut self, open: usize, close: usize, kind: SymbolKind, parent: usize, types: &[(String, Type)]) {
+        for (from, to) in self.split_commas(open, close) {
+            let mut name = from;
+            if self.is(name, "mut") {
+                name += 1;
+            }
+
+            if !self.is_ident(name) {
+                continue;
+            }
+
+            let decl = (self.tokens[from].start, self.tokens[to].end);
+            let id = self.declare(name, kind, decl, Some(parent));
+            let resolved = types
+                .iter()
+                .find(|(field, _)| field == self.tokens[name].text)
+                .map(|(_, ty)| ty.clone());
+
+            let ty = match &resolved {
+                Some(ty) => Some(ty.to_string()),
+                None if self.is(name + 1, ":") && name + 2 <= to => Some(self.text(name + 2, to)),
+                None => None,
+            };
+
+            let symbol = &mut self.index.symbols[id];
+            symbol.ty = ty;
+            symbol.resolved = resolved;
+        }
+    }
+
+    // Top level declarations, in source order
+    fn declarations(&mut self) {
+        let mut functions = 0;
+        let mut i = 0;
+        while i < self.tokens.len() {
+            let token = self.tokens[i];
+            let next = if token.kind == TokenKind::Keyword {
+                match token.text {
+                    "fn" | "entry" | "hook" => {
+                        functions += 1;
+                        self.function(i, functions - 1)
+                    }
+                    "struct" => self.structure(i),
+                    "enum" => self.enumeration(i),
+                    "const" => self.constant(i),
+                    "import" => self.import(i),
+                    _ => None,
+                }
+            } else {
+                None
+            };
+
+            i = match next {
+                Some(next) => next.max(i + 1),
+                None => match token.text {
+                    "{" | "(" | "[" => self.closing(i).map(|c| c + 1).unwrap_or(i + 1),
+                    _ => i + 1,
+                },
+            };
+        }
+
+        // The functions are matched with the program by position
+        if functions != self.index.functions.len() {
+            self.unresolved((0, self.source.len()));
+        }
+    }
+
+    fn function(&mut self, keyword: usize, position: usize) -> Option<usize> {
+        let kind = match self.tokens[keyword].text {
+            "entry" => SymbolKind::Entry,
+            "hook" => SymbolKind::Hook,
+            _ => SymbolKind::Function,
+        };
+
+        let mut i = keyword + 1;
+        // Receiver of a function declared on a type: `fn (self Type) name()`
+        let mut receiver = None;
+        if kind == SymbolKind::Function && self.is(i, "(") {
+            let close = self.closing(i)?;
+            receiver = Some((i, close));
+            i = close + 1;
+        }
+
+        if !self.is_ident(i) || !self.is(i + 1, "(") {
+            return None;
+        }
+
+        let name = i;
+        let params_close = self.closing(i + 1)?;
+        let mut j = params_close + 1;
+        while j < self.tokens.len() && !self.is(j, "{") && !self.is(j, ";") {
+            j += 1;
+        }
+
+        let body = self.is(j, "{").then_some(j);
+        let body_close = body.and_then(|b| self.closing(b));
+        let end = body_close.unwrap_or(j.min(self.tokens.len() - 1));
+
+        let decl = (self.tokens[keyword].start, self.tokens[end].end);
+        let id = self.declare(name, kind, decl, None);
+        self.top_level.entry(self.tokens[name].text.to_owned()).or_insert(id);
+        self.index.symbols[id].doc = self.doc_before(decl.0);
+
+        // Function of the program at the same position, it must have the same name
+        let parsed = self.parsed;
+        let function = parsed.program.functions().get(position);
+        let mapping = parsed.mapper
+            .functions()
+            .get_function(&(position as u16 + self.parsed.env_offset))
+            .filter(|mapping| mapping.name == self.tokens[name].text);
+
+        let (Some(function), Some(mapping)) = (function, mapping) else {
+            self.unresolved(decl);
+            return Some(end + 1);
+        };
+
+        self.index.functions[position] = Some(id);
+        let parameters: Vec<(String, Type)> = mapping.parameters
+            .iter()
+            .map(|(name, ty)| (name.to_string(), ty.clone()))
+            .collect();
+
+        let symbol = &mut self.index.symbols[id];
+        symbol.on_type = mapping.on_type.as_ref().map(Type::to_string);
+        // Entries always return an exit code
+        symbol.resolved = match kind {
+            SymbolKind::Entry => Some(Type::U64),
+            _ => function.return_type().clone(),
+        };
+        symbol.ty = symbol.resolved.as_ref().map(Type::to_string);
+
+        // The instance comes first in the variables of the function
+        let mut bindings = Vec::new();
+        if let Some((open, close)) = receiver {
+            let declared = self.index.symbols.len();
+            self.typed_names(open, close, SymbolKind::Parameter, id, &[]);
+            // Receiver is not an argument of the signature
+            self.index.symbols[id].children.clear();
+            if let Some(instance) = self.index.symbols.get_mut(declared) {
+                instance.parent = Some(id);
+                instance.resolved = mapping.on_type.clone();
+                instance.ty = mapping.on_type.as_ref().map(Type::to_string);
+                bindings.push((0, declared));
+            }
+        }
+        self.typed_names(name + 1, params_close, SymbolKind::Parameter, id, &parameters);
+
+        let (Some(open), Some(close)) = (body, body_close) else {
+            return Some(end + 1);
+        };
+
+        let offset = bindings.len();
+        let declared: Vec<usize> = (id + 1..self.index.symbols.len()).collect();
+        for param in declared {
+            let symbol = &mut self.index.symbols[param];
+            symbol.visible_start = self.tokens[open].end;
+            symbol.visible_end = self.tokens[close].start;
+            if bindings.iter().any(|(_, instance)| *instance == param) {
+                continue;
+            }
+
+            // Variable ids of the parameters follow their order in the program
+            match parameters.iter().position(|(name, _)| *name == symbol.name) {
+                Some(variable) => bindings.push(((variable + offset) as IdentifierType, param)),
+                None => self.index.unresolved.push(decl),
+            }
+        }
+
+        self.bodies.push(Body {
+            symbol: id,
+            position,
+            open,
+            close,
+            parameters: bindings,
+        });
+
+        Some(end + 1)
+    }
+
+    fn structure(&mut self, keyword: usize) -> Option<usize> {
+        let name = keyword + 1;
+        if !self.is_ident(name) || !self.is(name + 1, "{") {
+            return None;
+        }
+
+        let close = self.closing(name + 1)?;
+        let decl = (self.tokens[keyword].start, self.tokens[close].end);
+        let id = self.declare(name, SymbolKind::Struct, decl, None);
+        self.top_level.entry(self.tokens[name].text.to_owned()).or_insert(id);
+        self.index.symbols[id].doc = self.doc_before(decl.0);
+
+        let fields: Vec<(String, Type)> = self.parsed.program
+            .structures()
+            .iter()
+            .find(|ty| ty.name() == self.tokens[name].text)
+            .map(|ty| ty.fields()
+                .iter()
+                .map(|(name, ty)| (name.to_string(), ty.clone()))
+                .collect())
+            .unwrap_or_default();
+        self.index.symbols[id].resolved = self.named_type(self.tokens[name].text);
+        self.typed_names(name + 1, close, SymbolKind::Field, id, &fields);
+
+        Some(close + 1)
+    }
+
+    fn enumeration(&mut self, keyword: usize) -> Option<usize> {
+        let name = keyword + 1;
+        if !self.is_ident(name) || !self.is(name + 1, "{") {
+            return None;
+        }
+
+        let close = self.closing(name + 1)?;
+        let decl = (self.tokens[keyword].start, self.tokens[close].end);
+        let id = self.declare(name, SymbolKind::Enum, decl, None);
+        self.top_level.entry(self.tokens[name].text.to_owned()).or_insert(id);
+        self.index.symbols[id].doc = self.doc_before(decl.0);
+        self.index.symbols[id].resolved = self.named_type(self.tokens[name].text);
+
+        let variants: Vec<(String, Vec<(String, Type)>)> = self.parsed.program
+            .enums()
+            .iter()
+            .find(|ty| ty.name() == self.tokens[name].text)
+            .map(|ty| ty.variants()
+                .iter()
+                .map(|(name, variant)| {
+                    let fields = variant.fields()
+                        .iter()
+                        .map(|(name, ty)| (name.to_string(), ty.clone()))
+                        .collect();
+                    (name.to_string(), fields)
+                })
+                .collect())
+            .unwrap_or_default();
+
+        for (from, to) in self.split_commas(name + 1, close) {
+            if !self.is_ident(from) {
+                continue;
+            }
+
+            let decl = (self.tokens[from].start, self.tokens[to].end);
+            let variant = self.declare(from, SymbolKind::Variant, decl, Some(id));
+            if self.is(from + 1, "{") {
+                if let Some(fields_close) = self.closing(from + 1) {
+                    let fields = variants
+                        .iter()
+                        .find(|(name, _)| name == self.tokens[from].text)
+                        .map(|(_, fields)| fields.as_slice())
+                        .unwrap_or_default();
+                    self.typed_names(from + 1, fields_close, SymbolKind::Field, variant, fields);
+                }
+            }
+        }
+
+        Some(close + 1)
+    }
+
+    // Constants are replaced by their value in the program, their declaration is read as written
+    fn constant(&mut self, keyword: usize) -> Option<usize> {
+        let name = keyword + 1;
+        if !self.is_ident(name) {
+            return None;
+        }
+
+        let end = self.statement_end(name, self.tokens.len());
+        let last = end.min(self.tokens.len() - 1);
+        let decl = (self.tokens[keyword].start, self.tokens[last].end);
+        let id = self.declare(name, SymbolKind::Constant, decl, None);
+        self.top_level.entry(self.tokens[name].text.to_owned()).or_insert(id);
+        self.index.symbols[id].doc = self.doc_before(decl.0);
+
+        let assign = (name..end).find(|i| self.is(*i, "="));
+        if self.is(name + 1, ":") {
+            let type_end = assign.unwrap_or(end);
+            if name + 2 < type_end {
+                let ty = self.text(name + 2, type_end - 1);
+                self.index.symbols[id].resolved = parse_type_name(&ty, &|name| self.named_type(name));
+                self.index.symbols[id].ty = Some(ty);
+            }
+        }
+
+        if let Some(assign) = assign {
+            if assign + 1 < end {
+                self.index.symbols[id].value = Some(self.text(assign + 1, end - 1));
+            }
+        }
+
+        Some(end + 1)
+    }
+
+    fn import(&mut self, keyword: usize) -> Option<usize> {
+        let end = self.statement_end(keyword, self.tokens.len());
+        let last = end.min(self.tokens.len() - 1);
+        let decl = (self.tokens[keyword].start, self.tokens[last].end);
+
+        // `import "path" as alias;` is referenced by its alias
+        let name = match (keyword..end).find(|i| self.is(*i, "as")) {
+            Some(alias) if self.is_ident(alias + 1) => alias + 1,
+            _ if keyword + 1 < end => keyword + 1,
+            _ => return Some(end + 1),
+        };
+
+        let id = self.declare(name, SymbolKind::Import, decl, None);
+        let symbol = &mut self.index.symbols[id];
+        symbol.name = symbol.name.trim_matches(|c| c == '"' || c == '\'').to_owned();
+        if self.tokens[name].kind == TokenKind::Identifier {
+            self.top_level.entry(self.tokens[name].text.to_owned()).or_insert(id);
+        }
+
+        Some(end + 1)
+    }
+
+    // Match the statements of a function with the tokens of its body
+    fn body(&mut self, body: Body) {
+        let parsed = self.parsed;
+        let Some(function) = parsed.program.functions().get(body.position) else {
+            return;
+        };
+
+        let counts = (
+            self.index.symbols.len(),
+            self.index.occurrences.len(),
+            self.index.shadows.len(),
+            self.index.unreachable.len(),
+            self.index.expressions.len(),
+        );
+
+        let mut walk = Walk {
+            function: body.symbol,
+            cursor: body.open + 1,
+            end: body.close,
+            bindings: body.parameters,
+            failed: false,
+        };
+        self.statements(&mut walk, function.get_statements());
+
+        if walk.failed {
+            // What was matched before the failure can't be trusted
+            self.index.symbols.truncate(counts.0);
+            self.index.occurrences.truncate(counts.1);
+            self.index.shadows.truncate(counts.2);
+            self.index.unreachable.truncate(counts.3);
+            self.index.expressions.truncate(counts.4);
+            for claimed in &mut self.claimed[body.open..=body.close] {
+                *claimed = false;
+            }
+
+            let symbol = &self.index.symbols[body.symbol];
+            self.index.unresolved.push((symbol.decl_start, symbol.decl_end));
+        }
+    }
+
+    fn statements(&mut self, walk: &mut Walk, statements: &[Statement]) {
+        let scope = walk.bindings.len();
+        let mut diverged = false;
+        let mut reported = false;
+
+        for statement in statements {
+            let start = self.statement(walk, statement);
+            if let (true, false, Some(start)) = (diverged, reported, start) {
+                // Everything after the statement in the same block is unreachable
+                if let Some(close) = self.enclosing_close(start) {
+                    self.index.unreachable.push((self.tokens[start].start, self.tokens[close - 1].end));
+                    reported = true;
+                }
+            }
+
+            diverged |= warnings::diverges(statement);
+        }
+
+        walk.bindings.truncate(scope);
+    }
+
+    // Next keyword starting a statement
+    fn keyword(&mut self, walk: &mut Walk, keyword: &str) -> Option<usize> {
+        let found = (walk.cursor..walk.end)
+            .find(|i| self.tokens[*i].kind == TokenKind::Keyword && self.tokens[*i].text == keyword);
+
+        match found {
+            Some(i) => {
+                walk.cursor = i + 1;
+                Some(i)
+            }
+            None => {
+                walk.failed = true;
+                None
+            }
+        }
+    }
+
+    // Variable declared right after its keyword
+    // `statement` is false for the loop variables, which have no statement of their own
+    fn local(&mut self, walk: &mut Walk, keyword: Option<usize>, ty: Option<Type>, statement: bool) -> Option<usize> {
+        let keyword = keyword?;
+        let mut name = keyword + 1;
+        if self.is(name, "mut") {
+            name += 1;
+        }
+
+        if name >= walk.end || !self.is_ident(name) {
+            walk.failed = true;
+            return None;
+        }
+
+        let last = if statement {
+            self.statement_end(name, walk.end).min(self.tokens.len() - 1)
+        } else {
+            name
+        };
+
+        let decl = (self.tokens[keyword].start, self.tokens[last].end);
+        let id = self.declare(name, SymbolKind::Local, decl, None);
+        let scope_end = self.enclosing_close(keyword)
+            .map(|close| self.tokens[close].start)
+            .unwrap_or(self.source.len());
+
+        let previous = walk.bindings
+            .iter()
+            .rev()
+            .map(|(_, symbol)| *symbol)
+            .find(|symbol| self.index.symbols[*symbol].name == self.tokens[name].text);
+        if let Some(previous) = previous {
+            self.index.shadows.push((id, previous));
+        }
+
+        let local = &mut self.index.symbols[id];
+        local.parent = Some(walk.function);
+        local.ty = ty.as_ref().map(Type::to_string);
+        local.resolved = ty;
+        local.visible_start = if statement { decl.1 } else { self.tokens[name].start };
+        local.visible_end = scope_end;
+
+        walk.cursor = name + 1;
+        Some(id)
+    }
+
+    fn bind(&mut self, walk: &mut Walk, variable: IdentifierType, local: Option<usize>) {
+        if let Some(local) = local {
+            walk.bindings.push((variable, local));
+        }
+    }
+
+    // Match a statement and return the index of its first token
+    fn statement(&mut self, walk: &mut Walk, statement: &Statement) -> Option<usize> {
+        let occurrences = self.index.occurrences.len();
+        let start = match statement {
+            Statement::Variable(declaration) => {
+                let keyword = self.keyword(walk, "let");
+                let local = self.local(walk, keyword, Some(declaration.value_type.clone()), true);
+                self.expression(walk, &declaration.value);
+                self.bind(walk, declaration.id, local);
+                keyword
+            }
+            Statement::For(declaration, condition, increment, block) => {
+                let scope = walk.bindings.len();
+                let keyword = self.keyword(walk, "for");
+                let local = self.local(walk, keyword, Some(declaration.value_type.clone()), false);
+                self.expression(walk, &declaration.value);
+                self.bind(walk, declaration.id, local);
+                self.expression(walk, condition);
+                self.expression(walk, increment);
+                self.statements(walk, block);
+                walk.bindings.truncate(scope);
+                keyword
+            }
+            Statement::ForEach(variable, values, block) => {
+                let scope = walk.bindings.len();
+                let keyword = self.keyword(walk, "foreach");
+                let ty = match self.expression_type(walk, values) {
+                    Some(Type::Array(inner)) => Some(*inner),
+                    _ => None,
+                };
+                let local = self.local(walk, keyword, ty, false);
+                self.expression(walk, values);
+                self.bind(walk, *variable, local);
+                self.statements(walk, block);
+                walk.bindings.truncate(scope);
+                keyword
+            }
+            Statement::ForEachRange(variable, start, end, block) => {
+                let scope = walk.bindings.len();
+                let keyword = self.keyword(walk, "foreach");
+                let ty = self.expression_type(walk, start);
+                let local = self.local(walk, keyword, ty, false);
+                self.expression(walk, start);
+                self.expression(walk, end);
+                self.bind(walk, *variable, local);
+                self.statements(walk, block);
+                walk.bindings.truncate(scope);
+                keyword
+            }
+            Statement::While(condition, block) => {
+                let keyword = self.keyword(walk, "while");
+                self.expression(walk, condition);
+                self.statements(walk, block);
+                keyword
+            }
+            Statement::If(condition, then, otherwise) => {
+                let keyword = self.keyword(walk, "if");
+                self.expression(walk, condition);
+                self.statements(walk, then);
+                if let Some(otherwise) = otherwise {
+                    self.statements(walk, otherwise);
+                }
+                keyword
+            }
+            Statement::Match(value, arms, default) => {
+                let keyword = self.keyword(walk, "match");
+                self.expression(walk, value);
+                for (pattern, arm) in arms {
+                    self.expression(walk, pattern);
+                    self.statements(walk, arm);
+                }
+                if let Some(default) = default {
+                    self.statements(walk, default);
+                }
+                keyword
+            }
+            Statement::Return(value) => {
+                let keyword = self.keyword(walk, "return");
+                if let Some(value) = value {
+                    self.expression(walk, value);
+                }
+                keyword
+            }
+            Statement::Break => self.keyword(walk, "break"),
+            Statement::Continue => self.keyword(walk, "continue"),
+            Statement::Block(block) => {
+                let open = (walk.cursor..walk.end).find(|i| self.is(*i, "{"));
+                match open {
+                    Some(open) => walk.cursor = open + 1,
+                    None => walk.failed = true,
+                }
+                self.statements(walk, block);
+                open
+            }
+            Statement::Expression(expression) => {
+                self.expression(walk, expression);
+                None
+            }
+        };
+
+        start.or_else(|| self.index.occurrences
+            .get(occurrences)
+            .and_then(|occurrence| self.token_at(occurrence.start)))
+    }
+
+    // Locate the next identifier with this name
+    fn claim(&mut self, walk: &mut Walk, name: &str, target: Target) {
+        let found = (walk.cursor..walk.end)
+            .find(|i| !self.claimed[*i] && self.is_ident(*i) && self.tokens[*i].text == name);
+
+        match found {
+            Some(i) => {
+                self.claimed[i] = true;
+                self.push_occurrence(i, target);
+                walk.cursor = i + 1;
+            }
+            None => walk.failed = true,
+        }
+    }
+
+    fn expression(&mut self, walk: &mut Walk, expression: &Expression) {
+        let occurrences = self.index.occurrences.len();
+        match expression {
+            Expression::Variable(id) => match walk.binding(*id) {
+                Some(symbol) => {
+                    let name = self.index.symbols[symbol].name.clone();
+                    self.claim(walk, &name, Target::Symbol(symbol));
+                }
+                None => walk.failed = true,
+            },
+            Expression::FunctionCall(receiver, id, arguments) => {
+                if let Some(receiver) = receiver {
+                    self.expression(walk, receiver);
+                }
+                self.call(walk, *id);
+                for argument in arguments {
+                    self.expression(walk, argument);
+                }
+            }
+            Expression::Path(left, right) => {
+                self.expression(walk, left);
+                self.field(walk, left, right);
+            }
+            Expression::StructConstructor(values, _) | Expression::EnumConstructor(values, _) => {
+                self.constructor(walk, values);
+            }
+            Expression::ArrayCall(left, right)
+            | Expression::Operator(_, left, right)
+            | Expression::RangeConstructor(left, right) => {
+                self.expression(walk, left);
+                self.expression(walk, right);
+            }
+            Expression::ArrayConstructor(values) => {
+                for value in values {
+                    self.expression(walk, value);
+                }
+            }
+            Expression::MapConstructor(entries, _, _) => {
+                for (key, value) in entries {
+                    self.expression(walk, key);
+                    self.expression(walk, value);
+                }
+            }
+            Expression::Ternary(condition, then, otherwise) => {
+                self.expression(walk, condition);
+                self.expression(walk, then);
+                self.expression(walk, otherwise);
+            }
+            Expression::SubExpression(inner) | Expression::IsNot(inner) | Expression::Cast(inner, _) => {
+                self.expression(walk, inner);
+            }
+            // Values, and constants replaced by their value, have no name to locate
+            _ => {}
+        }
+
+        // Range from the first name located in the expression up to the delimiters closing it
+        let first = self.index.occurrences.get(occurrences).and_then(|o| self.token_at(o.start));
+        let last = self.index.occurrences.last().and_then(|o| self.token_at(o.start));
+        if let (Some(first), Some(last)) = (first, last.filter(|_| self.index.occurrences.len() > occurrences)) {
+            let last = self.expression_end(first, last);
+            if let Some(ty) = self.expression_type(walk, expression) {
+                self.index.expressions.push((self.tokens[first].start, self.tokens[last].end, ty));
+            }
+        }
+    }
+
+    // Last token of an expression: the arguments of a call or an index following its last name,
+    // and the delimiters opened inside the expression
+    fn expression_end(&self, first: usize, mut last: usize) -> usize {
+        loop {
+            let next = last + 1;
+            last = match self.token(next).map(|t| t.text) {
+                Some("(" | "[") => match self.closing(next) {
+                    Some(close) => close,
+                    None => return last,
+                },
+                Some(")" | "]") if self.closing(next).is_some_and(|open| open >= first) => next,
+                _ => return last,
+            };
+        }
+    }
+
+    // Function called, by its id in the mapper
+    fn call(&mut self, walk: &mut Walk, id: u16) {
+        let parsed = self.parsed;
+        let Some(function) = parsed.mapper.functions().get_function(&id) else {
+            walk.failed = true;
+            return;
+        };
+
+        let name = function.name.to_string();
+        let on_type = function.on_type.as_ref().map(Type::to_string);
+        let target = match id.checked_sub(self.parsed.env_offset) {
+            Some(position) => match self.index.functions.get(position as usize).copied().flatten() {
+                Some(symbol) => Target::Symbol(symbol),
+                None => {
+                    walk.failed = true;
+                    return;
+                }
+            },
+            None if function.require_instance => Target::Method {
+                name: name.clone(),
+                receiver: on_type,
+            },
+            None => Target::Function {
+                name: name.clone(),
+                on_type,
+            },
+        };
+
+        self.claim(walk, &name, target);
+    }
+
+    // Field read on a struct, the program keeps its index in the struct
+    fn field(&mut self, walk: &mut Walk, left: &Expression, right: &Expression) {
+        let Expression::Variable(index) = right else {
+            walk.failed = true;
+            return;
+        };
+
+        let field = self.expression_type(walk, left)
+            .map(|ty| ty.to_string())
+            .and_then(|ty| self.index
+                .fields_of_type(&ty)
+                .and_then(|fields| fields.get(*index as usize).copied()));
+
+        match field {
+            Some(field) => {
+                let name = self.index.symbols[field].name.clone();
+                self.claim(walk, &name, Target::Symbol(field));
+            }
+            None => walk.failed = true,
+        }
+    }
+
+    // Struct or variant built by the tokens starting at `i`, with the token of its name
+    fn constructed(&self, i: usize) -> Option<(usize, usize)> {
+        if self.claimed[i] || !self.is_ident(i) {
+            return None;
+        }
+
+        let symbol = self.top_level.get(self.tokens[i].text).copied()?;
+        match self.index.symbols[symbol].kind {
+            SymbolKind::Struct if self.is(i + 1, "{") => Some((i, symbol)),
+            SymbolKind::Enum if self.is(i + 1, "::") => {
+                let name = self.token(i + 2)?.text;
+                self.index.symbols[symbol].children
+                    .iter()
+                    .copied()
+                    .find(|variant| self.index.symbols[*variant].name == name)
+                    .map(|variant| (i + 2, variant))
+            }
+            _ => None,
+        }
+    }
+
+    // `Struct { field: value }` or `Enum::Variant { field: value }`
+    // The program lists the values in the order of the declaration of the fields
+    fn constructor(&mut self, walk: &mut Walk, values: &[Expression]) {
+        let Some((name, owner)) = (walk.cursor..walk.end).find_map(|i| self.constructed(i)) else {
+            walk.failed = true;
+            return;
+        };
+
+        if self.index.symbols[owner].kind == SymbolKind::Variant {
+            self.claimed[name - 2] = true;
+            self.push_occurrence(name - 2, self.index.symbols[owner].parent.map(Target::Symbol).unwrap_or(Target::Unknown));
+        }
+        self.claimed[name] = true;
+        self.push_occurrence(name, Target::Symbol(owner));
+
+        let fields = self.index.symbols[owner].children.clone();
+        if fields.len() != values.len() {
+            walk.failed = true;
+            return;
+        }
+
+        let Some(close) = self.is(name + 1, "{").then(|| self.closing(name + 1)).flatten() else {
+            walk.cursor = name + 1;
+            if !fields.is_empty() {
+                walk.failed = true;
+            }
+            return;
+        };
+
+        let parts = self.split_commas(name + 1, close);
+        let end = walk.end;
+        for (field, value) in fields.into_iter().zip(values) {
+            let part = parts
+                .iter()
+                .copied()
+                .find(|(from, _)| self.tokens[*from].text == self.index.symbols[field].name);
+            let Some((from, to)) = part else {
+                walk.failed = true;
+                continue;
+            };
+
+            self.claimed[from] = true;
+            self.push_occurrence(from, Target::Symbol(field));
+            if self.is(from + 1, ":") {
+                walk.cursor = from + 2;
+                walk.end = to + 1;
+                self.expression(walk, value);
+            } else if let Some(variable) = match value {
+                Expression::Variable(id) => walk.binding(*id),
+                _ => None,
+            } {
+                // `{ name }` is both the field and the variable, neither can be renamed alone
+                self.push_occurrence(from, Target::Symbol(variable));
+                self.index.ambiguous.push((self.tokens[from].start, self.tokens[from].end));
+            } else {
+                walk.failed = true;
+            }
+        }
+
+        walk.end = end;
+        walk.cursor = close + 1;
+    }
+
+    // Type resolved by the parser for an expression
+    fn expression_type(&self, walk: &Walk, expression: &Expression) -> Option<Type> {
+        match expression {
+            Expression::Variable(id) => self.index.symbols[walk.binding(*id)?].resolved.clone(),
+            Expression::FunctionCall(_, id, _) => self.parsed.mapper
+                .functions()
+                .get_function(id)?
+                .return_type
+                .clone(),
+            Expression::Path(left, right) => {
+                let Expression::Variable(index) = right.as_ref() else {
+                    return None;
+                };
+                let ty = self.expression_type(walk, left)?.to_string();
+                let field = *self.index.fields_of_type(&ty)?.get(*index as usize)?;
+                self.index.symbols[field].resolved.clone()
+            }
+            Expression::ArrayCall(values, _) => match self.expression_type(walk, values)? {
+                Type::Array(inner) => Some(*inner),
+                _ => None,
+            },
+            Expression::StructConstructor(_, ty) => Some(Type::Struct(ty.clone())),
+            Expression::SubExpression(inner) => self.expression_type(walk, inner),
+            _ => None,
+        }
+    }
+
+    // Whether a variable with this name is visible at the offset
+    fn variable_visible(&self, name: &str, offset: usize) -> bool {
+        self.index.symbols
+            .iter()
+            .any(|s| matches!(s.kind, SymbolKind::Local | SymbolKind::Parameter)
+                && s.name == name
+                && s.visible_start <= offset
+                && offset <= s.visible_end)
+    }
+
+    // Name left once the bodies are matched, declared at the top level or by the environment
+    fn global(&self, i: usize) -> Target {
+        let name = self.tokens[i].text;
+        let offset = self.tokens[i].start;
+        if self.index.unresolved.iter().any(|(start, end)| *start <= offset && offset < *end) {
+            return Target::Unknown;
+        }
+
+        // Member of the type before `::`
+        if i >= 2 && self.is(i - 1, "::") {
+            let owner = self.tokens[i - 2].text;
+            let id = self.top_level.get(owner).copied();
+            return match id.map(|id| self.index.symbols[id].kind) {
+                Some(SymbolKind::Enum) => self.index.symbols[id.unwrap_or_default()].children
+                    .iter()
+                    .copied()
+                    .find(|variant| self.index.symbols[*variant].name == name)
+                    .map(Target::Symbol)
+                    .unwrap_or(Target::Unknown),
+                Some(SymbolKind::Struct) => self.index
+                    .methods_of_type(owner)
+                    .find(|method| self.index.symbols[*method].name == name)
+                    .map(Target::Symbol)
+                    .unwrap_or(Target::Unknown),
+                _ => Target::Function {
+                    name: name.to_owned(),
+                    on_type: Some(owner.to_owned()),
+                },
+            };
+        }
+
+        // A variable the program didn't match would be hidden by the declaration
+        if self.variable_visible(name, offset) {
+            return Target::Unknown;
+        }
+
+        match self.top_level.get(name).map(|id| (*id, self.index.symbols[*id].kind)) {
+            Some((id, SymbolKind::Struct | SymbolKind::Enum | SymbolKind::Constant | SymbolKind::Import)) => Target::Symbol(id),
+            Some(_) => Target::Unknown,
+            None if (self.parsed.opaque)(name).is_some() => Target::Type(name.to_owned()),
+            None => Target::Unknown,
+        }
+    }
+
+    // Names that are not part of the statements of a body: types, constants and enum variants
+    fn names(&mut self) {
+        for i in 0..self.tokens.len() {
+            if self.claimed[i] {
+                continue;
+            }
+
+            let token = self.tokens[i];
+            let target = match token.kind {
+                // Builtin types are keywords of the lexer
+                TokenKind::Type => Target::Type(token.text.to_owned()),
+                TokenKind::Identifier => self.global(i),
+                _ => continue,
+            };
+
+            if target == Target::Unknown {
+                self.unresolved((token.start, token.end));
+            }
+            self.push_occurrence(i, target);
+        }
+    }
+}
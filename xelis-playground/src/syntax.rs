@@ -0,0 +1,142 @@
+// Tokens of the lexer with the comments, over the Silex source code
+// Invalid characters are kept as unknown tokens instead of stopping the lexer,
+// which makes it usable on incomplete code typed in an editor
+
+use crate::tokens;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    Identifier,
+    Keyword,
+    // Builtin types, which are keywords of the lexer
+    Type,
+    Number,
+    String,
+    Operator,
+    Punctuation,
+    Comment,
+    Unknown,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SyntaxToken<'a> {
+    pub kind: TokenKind,
+    pub text: &'a str,
+    // Byte offsets in the source code
+    pub start: usize,
+    pub end: usize,
+}
+
+impl<'a> SyntaxToken<'a> {
+    pub fn is_trivia(&self) -> bool {
+        self.kind == TokenKind::Comment
+    }
+}
+
+pub const KEYWORDS: &[&str] = &[
+    "fn", "entry", "hook", "struct", "enum", "const", "let", "mut", "if", "else", "for", "foreach",
+    "in", "while", "return", "break", "continue", "import", "as", "match", "true", "false", "null",
+    "self",
+];
+
+// Keywords starting a top level declaration
+pub const ITEM_KEYWORDS: &[&str] = &["fn", "entry", "hook", "struct", "enum", "const", "import"];
+
+pub fn scan(source: &str) -> Vec<SyntaxToken<'_>> {
+    tokens::tokenize(source)
+        .into_iter()
+        .map(|token| {
+            let span = token.span();
+            let text = &source[span.start_offset()..span.end_offset()];
+            let kind = match token.category().as_str() {
+                "keyword" if KEYWORDS.contains(&text) => TokenKind::Keyword,
+                "keyword" => TokenKind::Type,
+                "identifier" => TokenKind::Identifier,
+                "number" => TokenKind::Number,
+                "string" => TokenKind::String,
+                "operator" => TokenKind::Operator,
+                "punctuation" => TokenKind::Punctuation,
+                "comment" => TokenKind::Comment,
+                _ => TokenKind::Unknown,
+            };
+
+            SyntaxToken {
+                kind,
+                text,
+                start: span.start_offset(),
+                end: span.end_offset(),
+            }
+        })
+        .collect()
+}
+
+// Byte ranges of every top level declaration
+// A declaration goes from its keyword up to the next declaration
+pub fn top_level_items(source: &str, tokens: &[SyntaxToken]) -> Vec<(usize, usize)> {
+    let mut starts = Vec::new();
+    let mut depth = 0usize;
+
+    for token in tokens.iter().filter(|t| !t.is_trivia()) {
+        match token.text {
+            "{" | "(" | "[" if token.kind == TokenKind::Punctuation => depth += 1,
+            "}" | ")" | "]" if token.kind == TokenKind::Punctuation => depth = depth.saturating_sub(1),
+            _ if depth == 0 && token.kind == TokenKind::Keyword && ITEM_KEYWORDS.contains(&token.text) => {
+                starts.push(token.start);
+            }
+            _ => {}
+        }
+    }
+
+    starts
+        .iter()
+        .enumerate()
+        .map(|(i, start)| (*start, starts.get(i + 1).copied().unwrap_or(source.len())))
+        .collect()
+}
+
+// Name and body of a function, entry or hook declaration
+pub struct FunctionParts {
+    pub name: String,
+    // Byte range between the braces of the body
+    pub body: (usize, usize),
+}
+
+pub fn function_parts(tokens: &[SyntaxToken], start: usize, end: usize) -> Option<FunctionParts> {
+    let tokens: Vec<&SyntaxToken> = tokens
+        .iter()
+        .filter(|t| !t.is_trivia() && t.start >= start && t.end <= end)
+        .collect();
+
+    let keyword = tokens.first()?;
+    if !matches!(keyword.text, "fn" | "entry" | "hook") {
+        return None;
+    }
+
+    // The name is the identifier followed by the parameters,
+    // a receiver `(self T)` may come before it
+    let name = tokens
+        .windows(2)
+        .find(|pair| pair[0].kind == TokenKind::Identifier && pair[1].text == "(")
+        .map(|pair| pair[0].text.to_owned())?;
+
+    let mut depth = 0usize;
+    let mut open = None;
+    for token in tokens.iter().filter(|t| t.kind == TokenKind::Punctuation) {
+        match (token.text, open) {
+            ("(" | "[", None) => depth += 1,
+            (")" | "]", None) => depth = depth.saturating_sub(1),
+            ("{", None) if depth == 0 => open = Some((token.end, 0usize)),
+            ("{", Some((body, nested))) => open = Some((body, nested + 1)),
+            ("}", Some((body, 0))) => {
+                return Some(FunctionParts {
+                    name,
+                    body: (body, token.start),
+                });
+            }
+            ("}", Some((body, nested))) => open = Some((body, nested - 1)),
+            _ => {}
+        }
+    }
+
+    None
+}
@@ -0,0 +1,150 @@
+// Tokens of a source code as seen by the lexer, for syntax highlighting
+// The lexer drops the comments and stops at the first invalid character,
+// so comments are recovered from the gaps between the tokens and the lexer
+// is started again after each invalid character, reported as an error token.
+
+use wasm_bindgen::prelude::wasm_bindgen;
+use xelis_lexer::Lexer;
+
+use crate::span::{LineIndex, Span};
+
+#[wasm_bindgen]
+#[derive(Debug, Clone)]
+pub struct SourceToken {
+    // Name of the lexer token, `Comment` or `Error`
+    kind: String,
+    // Highlighting class: keyword, identifier, number, string, operator, punctuation, comment or error
+    category: String,
+    text: String,
+    span: Span,
+}
+
+#[wasm_bindgen]
+impl SourceToken {
+    pub fn kind(&self) -> String {
+        self.kind.clone()
+    }
+
+    pub fn category(&self) -> String {
+        self.category.clone()
+    }
+
+    pub fn text(&self) -> String {
+        self.text.clone()
+    }
+
+    pub fn span(&self) -> Span {
+        self.span
+    }
+}
+
+// Name of the variant of a lexer token
+fn variant_name(debug: &str) -> &str {
+    let end = debug
+        .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .unwrap_or(debug.len());
+    &debug[..end]
+}
+
+const PUNCTUATIONS: &[&str] = &[
+    "ParenthesisOpen", "ParenthesisClose", "BraceOpen", "BraceClose", "BracketOpen", "BracketClose",
+    "Comma", "Colon", "SemiColon", "Dot", "DoubleColon",
+];
+
+// Highlighting class of a lexer token, from its variant
+fn category(kind: &str, debug: &str) -> &'static str {
+    match kind {
+        "Identifier" => "identifier",
+        "Value" if debug.contains("String(") => "string",
+        // `true`, `false` and `null` are written as keywords
+        "Value" if debug.contains("Bool(") || debug.contains("Null") => "keyword",
+        "Value" => "number",
+        _ if kind.starts_with("Operator") => "operator",
+        _ if PUNCTUATIONS.contains(&kind) => "punctuation",
+        // Keywords and builtin types
+        _ => "keyword",
+    }
+}
+
+// Comments written between two tokens, the gap contains nothing else
+fn comments(code: &str, from: usize, to: usize, index: &LineIndex, tokens: &mut Vec<SourceToken>) -> usize {
+    let mut offset = from;
+    while offset < to {
+        let rest = &code[offset..to];
+        let trimmed = rest.trim_start();
+        let start = offset + rest.len() - trimmed.len();
+        let len = if trimmed.starts_with("//") {
+            trimmed.find('\n').unwrap_or(trimmed.len())
+        } else if trimmed.starts_with("/*") {
+            trimmed[2..].find("*/").map(|end| end + 4).unwrap_or(trimmed.len())
+        } else {
+            return start;
+        };
+
+        tokens.push(SourceToken {
+            kind: "Comment".to_owned(),
+            category: "comment".to_owned(),
+            text: code[start..start + len].to_owned(),
+            span: index.span(start, start + len),
+        });
+        offset = start + len;
+    }
+
+    to
+}
+
+pub fn tokenize(code: &str) -> Vec<SourceToken> {
+    let index = LineIndex::new(code);
+    let mut tokens = Vec::new();
+    // Offset from which the lexer is started
+    let mut resume = 0;
+
+    loop {
+        let part = &code[resume..];
+        let part_index = LineIndex::new(part);
+        let mut last_end = resume;
+        let mut failed = false;
+
+        for token in Lexer::new(part) {
+            match token {
+                Ok(token) => {
+                    let start = resume + part_index.offset(token.line, token.column_start);
+                    let end = resume + part_index.offset(token.line, token.column_end);
+                    comments(code, last_end, start, &index, &mut tokens);
+
+                    let debug = format!("{:?}", token.token);
+                    let kind = variant_name(&debug);
+                    tokens.push(SourceToken {
+                        category: category(kind, &debug).to_owned(),
+                        kind: kind.to_owned(),
+                        text: code[start..end.max(start)].to_owned(),
+                        span: index.span(start, end),
+                    });
+                    last_end = end.max(start);
+                }
+                Err(_) => {
+                    failed = true;
+                    break;
+                }
+            }
+        }
+
+        let invalid = comments(code, last_end, code.len(), &index, &mut tokens);
+        if !failed || invalid >= code.len() {
+            break;
+        }
+
+        // Report the first invalid character and lex again after it
+        let len = code[invalid..].chars().next().map(char::len_utf8).unwrap_or(1);
+        tokens.push(SourceToken {
+            kind: "Error".to_owned(),
+            category: "error".to_owned(),
+            text: code[invalid..invalid + len].to_owned(),
+            span: index.span(invalid, invalid + len),
+        });
+        resume = invalid + len;
+    }
+
+    tokens.sort_by_key(|t| t.span.start_offset());
+    tokens
+}
@@ -0,0 +1,467 @@
+// Static checks of a compiled module, run on the opcodes decoded from the chunks
+// Jumps are byte offsets in their chunk, an invalid or unknown opcode stops the
+// analysis of its chunk and is reported as an error.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use wasm_bindgen::prelude::wasm_bindgen;
+use xelis_bytecode::{Module, OpCode};
+
+use crate::{
+    diagnostic::DiagnosticSeverity,
+    disassembly::hooks_by_chunk,
+    listing::{ChunkListing, Instruction},
+    Func,
+};
+
+#[wasm_bindgen]
+#[derive(Debug, Clone)]
+pub struct VerifyFinding {
+    code: &'static str,
+    severity: DiagnosticSeverity,
+    message: String,
+    chunk_id: usize,
+    // Index of the instruction in the chunk
+    instruction: Option<usize>,
+}
+
+#[wasm_bindgen]
+impl VerifyFinding {
+    pub fn code(&self) -> String {
+        self.code.to_owned()
+    }
+
+    pub fn severity_name(&self) -> String {
+        self.severity.as_str().to_owned()
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == DiagnosticSeverity::Error
+    }
+
+    pub fn message(&self) -> String {
+        self.message.clone()
+    }
+
+    pub fn chunk_id(&self) -> usize {
+        self.chunk_id
+    }
+
+    pub fn instruction(&self) -> Option<usize> {
+        self.instruction
+    }
+}
+
+#[wasm_bindgen]
+#[derive(Debug, Clone)]
+pub struct ChunkAnalysis {
+    chunk_id: usize,
+    // Called from an entry or a hook
+    reachable: bool,
+    max_stack_depth: usize,
+    // Chunks invoked by this one
+    calls: Vec<usize>,
+}
+
+#[wasm_bindgen]
+impl ChunkAnalysis {
+    pub fn chunk_id(&self) -> usize {
+        self.chunk_id
+    }
+
+    pub fn is_reachable(&self) -> bool {
+        self.reachable
+    }
+
+    pub fn max_stack_depth(&self) -> usize {
+        self.max_stack_depth
+    }
+
+    pub fn calls(&self) -> Vec<usize> {
+        self.calls.clone()
+    }
+}
+
+#[wasm_bindgen]
+#[derive(Debug, Clone)]
+pub struct VerifyReport {
+    findings: Vec<VerifyFinding>,
+    chunks: Vec<ChunkAnalysis>,
+}
+
+#[wasm_bindgen]
+impl VerifyReport {
+    pub fn findings(&self) -> Vec<VerifyFinding> {
+        self.findings.clone()
+    }
+
+    pub fn chunks(&self) -> Vec<ChunkAnalysis> {
+        self.chunks.clone()
+    }
+
+    pub fn error_count(&self) -> usize {
+        self.findings
+            .iter()
+            .filter(|f| f.is_error())
+            .count()
+    }
+
+    // No error was found, warnings are allowed
+    pub fn is_valid(&self) -> bool {
+        self.error_count() == 0
+    }
+
+    pub fn to_json(&self) -> String {
+        let findings: Vec<_> = self.findings
+            .iter()
+            .map(|finding| serde_json::json!({
+                "code": finding.code,
+                "severity": finding.severity.as_str(),
+                "message": finding.message,
+                "chunk_id": finding.chunk_id,
+                "instruction": finding.instruction,
+            }))
+            .collect();
+
+        let chunks: Vec<_> = self.chunks
+            .iter()
+            .map(|chunk| serde_json::json!({
+                "chunk_id": chunk.chunk_id,
+                "reachable": chunk.reachable,
+                "max_stack_depth": chunk.max_stack_depth,
+                "calls": chunk.calls,
+            }))
+            .collect();
+
+        serde_json::json!({
+            "valid": self.is_valid(),
+            "findings": findings,
+            "chunks": chunks,
+        }).to_string()
+    }
+}
+
+fn finding(code: &'static str, severity: DiagnosticSeverity, message: String, chunk_id: usize, instruction: Option<usize>) -> VerifyFinding {
+    VerifyFinding {
+        code,
+        severity,
+        message,
+        chunk_id,
+        instruction,
+    }
+}
+
+// Values popped and pushed by an instruction
+fn stack_effect(module: &Module, instruction: &Instruction, syscalls: Option<&HashMap<u16, Func>>) -> (usize, usize) {
+    let count = |index| instruction.operand(index).unwrap_or(0) as usize;
+    match instruction.opcode {
+        OpCode::Constant | OpCode::MemoryLoad | OpCode::Copy | OpCode::CopyN | OpCode::IteratorNext => (0, 1),
+        OpCode::Pop | OpCode::MemorySet | OpCode::JumpIfFalse | OpCode::Return | OpCode::IteratorEnd => (1, 0),
+        OpCode::PopN => (count(0), 0),
+        OpCode::Jump | OpCode::Swap | OpCode::Swap2 => (0, 0),
+        OpCode::SubLoad | OpCode::Cast | OpCode::Neg | OpCode::Not | OpCode::IterableLength
+        | OpCode::IteratorBegin | OpCode::Inc | OpCode::Dec => (1, 1),
+        OpCode::ArrayCall | OpCode::NewRange => (2, 1),
+        OpCode::Add | OpCode::Sub | OpCode::Mul | OpCode::Div | OpCode::Mod | OpCode::Pow
+        | OpCode::And | OpCode::Or | OpCode::Xor | OpCode::Shl | OpCode::Shr | OpCode::Eq
+        | OpCode::Neq | OpCode::Gt | OpCode::Gte | OpCode::Lt | OpCode::Lte => (2, 1),
+        OpCode::Assign | OpCode::AssignAdd | OpCode::AssignSub | OpCode::AssignMul | OpCode::AssignDiv
+        | OpCode::AssignMod | OpCode::AssignPow | OpCode::AssignAnd | OpCode::AssignOr
+        | OpCode::AssignXor | OpCode::AssignShl | OpCode::AssignShr => (2, 0),
+        OpCode::NewArray => (count(0), 1),
+        OpCode::NewMap => (count(0) * 2, 1),
+        // Constructors pop the values of their fields
+        OpCode::NewStruct => {
+            let fields = module.structs()
+                .get(count(0))
+                .map(|ty| ty.fields().len())
+                .unwrap_or(0);
+            (fields, 1)
+        }
+        OpCode::NewEnum => {
+            let fields = module.enums()
+                .get(count(0))
+                .and_then(|ty| ty.get_variant(count(1) as u8))
+                .map(|variant| variant.fields().len())
+                .unwrap_or(0);
+            (fields, 1)
+        }
+        // Arguments count follows the id and the receiver flag
+        OpCode::SysCall => {
+            let returns = instruction
+                .operand(0)
+                .and_then(|id| syscalls?.get(&(id as u16)))
+                .map(|f| f.return_type.is_some() as usize)
+                .unwrap_or(1);
+            (count(2) + count(1), returns)
+        }
+        OpCode::InvokeChunk => (count(2) + count(1), 1),
+    }
+}
+
+// Maximum stack depth over every path of the chunk
+// A path popping more values than the stack holds, or reaching an instruction
+// with another depth than a previous path, is reported as an error
+fn analyze_chunk(module: &Module, chunk: &ChunkListing, syscalls: Option<&HashMap<u16, Func>>, findings: &mut Vec<VerifyFinding>) -> usize {
+    let mut depths: Vec<Option<usize>> = vec![None; chunk.instructions.len()];
+    let mut mismatches = HashSet::new();
+    let mut queue = VecDeque::new();
+    let mut max_depth = 0;
+    if !chunk.instructions.is_empty() {
+        depths[0] = Some(0);
+        queue.push_back(0);
+    }
+
+    while let Some(i) = queue.pop_front() {
+        let instruction = &chunk.instructions[i];
+        let depth = depths[i].unwrap_or(0);
+        let (pops, pushes) = stack_effect(module, instruction, syscalls);
+        if pops > depth {
+            findings.push(finding(
+                "stack_underflow",
+                DiagnosticSeverity::Error,
+                format!("`{}` pops {} values but the stack only holds {}", instruction.text(), pops, depth),
+                chunk.id,
+                Some(i),
+            ));
+            continue;
+        }
+
+        let next_depth = depth - pops + pushes;
+        max_depth = max_depth.max(depth).max(next_depth);
+
+        let mut successors = Vec::new();
+        match instruction.jump_kind() {
+            Some(conditional) => {
+                match instruction.jump_target().and_then(|offset| chunk.instruction_at(offset)) {
+                    // An exhausted iterator jumps without pushing its next value
+                    Some(target) if instruction.opcode == OpCode::IteratorNext => successors.push((target, depth - pops)),
+                    Some(target) => successors.push((target, next_depth)),
+                    None => findings.push(finding(
+                        "invalid_jump",
+                        DiagnosticSeverity::Error,
+                        format!("`{}` jumps outside of the chunk", instruction.text()),
+                        chunk.id,
+                        Some(i),
+                    )),
+                }
+
+                if conditional {
+                    successors.push((i + 1, next_depth));
+                }
+            }
+            None if instruction.is_return() => {}
+            None => successors.push((i + 1, next_depth)),
+        }
+
+        // Each instruction is analyzed once, the other paths must reach it with the same depth
+        for (successor, successor_depth) in successors.into_iter().filter(|(s, _)| *s < depths.len()) {
+            match depths[successor] {
+                None => {
+                    depths[successor] = Some(successor_depth);
+                    queue.push_back(successor);
+                }
+                Some(expected) if expected != successor_depth && mismatches.insert(successor) => {
+                    findings.push(finding(
+                        "stack_mismatch",
+                        DiagnosticSeverity::Error,
+                        format!(
+                            "`{}` is reached with stack depths {} and {}",
+                            chunk.instructions[successor].text(),
+                            expected,
+                            successor_depth,
+                        ),
+                        chunk.id,
+                        Some(successor),
+                    ));
+                }
+                Some(_) => {}
+            }
+        }
+    }
+
+    max_depth
+}
+
+// Chunks taking part in a recursion, grouped by cycle
+fn recursive_groups(calls: &[Vec<usize>]) -> Vec<Vec<usize>> {
+    // Tarjan's strongly connected components
+    struct State<'a> {
+        calls: &'a [Vec<usize>],
+        index: usize,
+        indexes: Vec<Option<usize>>,
+        lowlinks: Vec<usize>,
+        stack: Vec<usize>,
+        on_stack: Vec<bool>,
+        groups: Vec<Vec<usize>>,
+    }
+
+    fn visit(state: &mut State, chunk: usize) {
+        state.indexes[chunk] = Some(state.index);
+        state.lowlinks[chunk] = state.index;
+        state.index += 1;
+        state.stack.push(chunk);
+        state.on_stack[chunk] = true;
+
+        let calls = state.calls;
+        for &callee in &calls[chunk] {
+            match state.indexes[callee] {
+                None => {
+                    visit(state, callee);
+                    state.lowlinks[chunk] = state.lowlinks[chunk].min(state.lowlinks[callee]);
+                }
+                Some(index) if state.on_stack[callee] => {
+                    state.lowlinks[chunk] = state.lowlinks[chunk].min(index);
+                }
+                Some(_) => {}
+            }
+        }
+
+        if Some(state.lowlinks[chunk]) == state.indexes[chunk] {
+            let mut group = Vec::new();
+            while let Some(member) = state.stack.pop() {
+                state.on_stack[member] = false;
+                group.push(member);
+                if member == chunk {
+                    break;
+                }
+            }
+
+            if group.len() > 1 || state.calls[chunk].contains(&chunk) {
+                group.sort_unstable();
+                state.groups.push(group);
+            }
+        }
+    }
+
+    let mut state = State {
+        calls,
+        index: 0,
+        indexes: vec![None; calls.len()],
+        lowlinks: vec![0; calls.len()],
+        stack: Vec::new(),
+        on_stack: vec![false; calls.len()],
+        groups: Vec::new(),
+    };
+
+    for chunk in 0..calls.len() {
+        if state.indexes[chunk].is_none() {
+            visit(&mut state, chunk);
+        }
+    }
+
+    state.groups.sort();
+    state.groups
+}
+
+// Syscalls are only checked if the functions of the environment are known
+pub fn verify(
+    module: &Module,
+    listing: &[ChunkListing],
+    chunk_names: &HashMap<usize, String>,
+    syscalls: Option<&HashMap<u16, Func>>,
+) -> VerifyReport {
+    let chunk_count = module.chunks().len();
+    let name = |chunk_id: usize| chunk_names
+        .get(&chunk_id)
+        .map(|name| format!("`{}`", name))
+        .unwrap_or_else(|| format!("chunk {}", chunk_id));
+
+    let mut findings = Vec::new();
+    let mut calls = vec![Vec::new(); chunk_count];
+    let mut max_depths = vec![0; chunk_count];
+
+    for chunk in listing.iter().filter(|c| c.id < chunk_count) {
+        for (i, instruction) in chunk.instructions.iter().enumerate() {
+            if instruction.is_chunk_call() {
+                match instruction.operand(0).map(|id| id as usize) {
+                    Some(callee) if callee < chunk_count => {
+                        if !calls[chunk.id].contains(&callee) {
+                            calls[chunk.id].push(callee);
+                        }
+                    }
+                    _ => findings.push(finding(
+                        "invalid_chunk_call",
+                        DiagnosticSeverity::Error,
+                        format!("`{}` invokes a chunk that doesn't exist", instruction.text()),
+                        chunk.id,
+                        Some(i),
+                    )),
+                }
+            } else if instruction.is_syscall() {
+                let known = instruction
+                    .operand(0)
+                    .map(|id| syscalls.map(|s| s.contains_key(&(id as u16))).unwrap_or(true))
+                    .unwrap_or(false);
+
+                if !known {
+                    findings.push(finding(
+                        "unknown_syscall",
+                        DiagnosticSeverity::Error,
+                        format!("`{}` calls a syscall that the environment doesn't provide", instruction.text()),
+                        chunk.id,
+                        Some(i),
+                    ));
+                }
+            }
+        }
+
+        if let Some(error) = &chunk.error {
+            findings.push(finding(
+                "invalid_opcode",
+                DiagnosticSeverity::Error,
+                format!("{} at offset {} of {}", error.message, error.offset, name(chunk.id)),
+                chunk.id,
+                Some(chunk.instructions.len()),
+            ));
+        }
+
+        max_depths[chunk.id] = analyze_chunk(module, chunk, syscalls, &mut findings);
+    }
+
+    // Entries and hooks are the only chunks called from outside
+    let hooks = hooks_by_chunk(module);
+    let mut reachable = HashSet::new();
+    let mut queue: VecDeque<usize> = (0..chunk_count)
+        .filter(|id| module.is_entry_chunk(*id) || hooks.contains_key(id))
+        .collect();
+    while let Some(chunk_id) = queue.pop_front() {
+        if reachable.insert(chunk_id) {
+            queue.extend(calls[chunk_id].iter().copied());
+        }
+    }
+
+    for chunk_id in (0..chunk_count).filter(|id| !reachable.contains(id)) {
+        findings.push(finding(
+            "unreachable_chunk",
+            DiagnosticSeverity::Warning,
+            format!("{} is never called from an entry or a hook", name(chunk_id)),
+            chunk_id,
+            None,
+        ));
+    }
+
+    for group in recursive_groups(&calls) {
+        let names: Vec<String> = group.iter().map(|id| name(*id)).collect();
+        findings.push(finding(
+            "recursive_call",
+            DiagnosticSeverity::Warning,
+            format!("recursive calls through {}, the call depth is not bounded", names.join(", ")),
+            group[0],
+            None,
+        ));
+    }
+
+    findings.sort_by_key(|f| (f.chunk_id, f.instruction));
+    VerifyReport {
+        findings,
+        chunks: (0..chunk_count)
+            .map(|chunk_id| ChunkAnalysis {
+                chunk_id,
+                reachable: reachable.contains(&chunk_id),
+                max_stack_depth: max_depths[chunk_id],
+                calls: calls[chunk_id].clone(),
+            })
+            .collect(),
+    }
+}
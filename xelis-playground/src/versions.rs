@@ -0,0 +1,140 @@
+// Compatibility of a source with each contract version
+// The environment functions called by the module compiled for each version
+// are compared with the functions of every other version
+
+use std::collections::BTreeSet;
+
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use crate::{
+    listing,
+    CompileResult,
+    Diagnostic,
+    Func,
+    Program,
+};
+
+#[wasm_bindgen]
+#[derive(Debug, Clone)]
+pub struct VersionReport {
+    version: u8,
+    compiles: bool,
+    diagnostics: Vec<Diagnostic>,
+    // Size of the serialized module, none if it doesn't compile
+    module_size: Option<usize>,
+    missing_syscalls: Vec<String>,
+    changed_syscalls: Vec<String>,
+}
+
+#[wasm_bindgen]
+impl VersionReport {
+    pub fn version(&self) -> u8 {
+        self.version
+    }
+
+    pub fn compiles(&self) -> bool {
+        self.compiles
+    }
+
+    pub fn diagnostics(&self) -> Vec<Diagnostic> {
+        self.diagnostics.clone()
+    }
+
+    pub fn module_size(&self) -> Option<usize> {
+        self.module_size
+    }
+
+    // Functions used by the source that this version doesn't provide
+    pub fn missing_syscalls(&self) -> Vec<String> {
+        self.missing_syscalls.clone()
+    }
+
+    // Functions used by the source with another signature or syscall id in this version
+    pub fn changed_syscalls(&self) -> Vec<String> {
+        self.changed_syscalls.clone()
+    }
+}
+
+impl VersionReport {
+    pub fn new(version: u8, result: CompileResult, (missing_syscalls, changed_syscalls): (Vec<String>, Vec<String>)) -> Self {
+        Self {
+            version,
+            compiles: result.is_success(),
+            module_size: result.program.as_ref().map(|program| program.to_bytes().len()),
+            diagnostics: result.diagnostics,
+            missing_syscalls,
+            changed_syscalls,
+        }
+    }
+}
+
+// Types of the parameters, without their names
+fn parameter_types(function: &Func) -> Vec<&str> {
+    function.params
+        .iter()
+        .map(|param| param.split_once(": ").map_or(param.as_str(), |(_, ty)| ty))
+        .collect()
+}
+
+// Name with the parameter types, which tell the overloads apart
+fn qualified_name(function: &Func) -> String {
+    let name = match &function.on_type {
+        Some(ty) => format!("{}::{}", ty, function.name),
+        None => function.name.clone(),
+    };
+
+    format!("{}({})", name, parameter_types(function).join(", "))
+}
+
+// Overloads share a name, a function is identified by its parameter types too
+fn same_function(a: &Func, b: &Func) -> bool {
+    a.name == b.name
+        && a.on_type == b.on_type
+        && a.on_instance == b.on_instance
+        && parameter_types(a) == parameter_types(b)
+}
+
+// Environment functions called by the compiled program, `functions` being the
+// environment it was compiled with
+pub fn used_syscalls(program: &Program, functions: &[Func]) -> Vec<Func> {
+    let ids: BTreeSet<u16> = listing::decode(&program.module)
+        .iter()
+        .flat_map(|chunk| chunk.instructions.iter())
+        .filter(|instruction| instruction.is_syscall())
+        .filter_map(|instruction| instruction.operand(0))
+        .map(|id| id as u16)
+        .collect();
+
+    functions
+        .iter()
+        .filter(|function| ids.contains(&function.syscall_id))
+        .cloned()
+        .collect()
+}
+
+// Functions called by the modules of the versions that are missing or changed in `functions`
+// A function is compared with its definition in the first version calling it
+pub fn syscall_changes(used: &[Vec<Func>], functions: &[Func]) -> (Vec<String>, Vec<String>) {
+    let mut missing = BTreeSet::new();
+    let mut changed = BTreeSet::new();
+    let mut compared: Vec<&Func> = Vec::new();
+
+    for expected in used.iter().flatten() {
+        if compared.iter().any(|f| same_function(f, expected)) {
+            continue;
+        }
+        compared.push(expected);
+
+        match functions.iter().find(|f| same_function(f, expected)) {
+            None => {
+                missing.insert(qualified_name(expected));
+            }
+            Some(function) if function.syscall_id != expected.syscall_id || function.signature() != expected.signature() => {
+                changed.insert(qualified_name(expected));
+            }
+            Some(_) => {}
+        }
+    }
+
+    (missing.into_iter().collect(), changed.into_iter().collect())
+}
@@ -0,0 +1,99 @@
+// Non-fatal findings computed on a program that parsed successfully
+// Control flow checks walk the statements of the parsed program,
+// the symbol index locates the reported names in the source
+
+use xelis_ast::{FunctionType, Statement};
+
+use crate::{
+    diagnostic::{Diagnostic, DiagnosticPhase, DiagnosticSeverity},
+    span::LineIndex,
+    symbols::{SymbolIndex, SymbolKind},
+};
+
+// Control flow of a parsed function
+pub struct FunctionFlow {
+    // Declaration of the function in the symbol index
+    pub symbol: Option<usize>,
+    pub is_entry: bool,
+    // Every path through the body ends with a return
+    pub always_returns: bool,
+}
+
+impl FunctionFlow {
+    pub fn new(symbol: Option<usize>, function: &FunctionType) -> Self {
+        Self {
+            symbol,
+            is_entry: function.is_entry(),
+            always_returns: always_returns(function.get_statements()),
+        }
+    }
+}
+
+fn always_returns(statements: &[Statement]) -> bool {
+    statements.iter().any(|statement| match statement {
+        Statement::Return(_) => true,
+        Statement::Block(block) => always_returns(block),
+        Statement::If(_, then, Some(otherwise)) => always_returns(then) && always_returns(otherwise),
+        Statement::Match(_, arms, Some(default)) => {
+            arms.iter().all(|(_, arm)| always_returns(arm)) && always_returns(default)
+        }
+        _ => false,
+    })
+}
+
+pub fn diverges(statement: &Statement) -> bool {
+    matches!(statement, Statement::Return(_) | Statement::Break | Statement::Continue)
+        || always_returns(std::slice::from_ref(statement))
+}
+
+pub fn collect(index: &SymbolIndex, lines: &LineIndex, flows: &[FunctionFlow]) -> Vec<Diagnostic> {
+    let mut warnings = Vec::new();
+    let mut warn = |code: &'static str, message: String, start: usize, end: usize| {
+        warnings.push(
+            Diagnostic::new(DiagnosticPhase::Compile, DiagnosticSeverity::Warning, message, Some(lines.span(start, end)))
+                .with_code(code)
+        );
+    };
+
+    for (id, symbol) in index.symbols.iter().enumerate() {
+        // Names starting by an underscore are explicitly unused
+        // and a reference may be missed where the program couldn't be matched
+        if symbol.name.starts_with('_') || !index.is_exact(id) {
+            continue;
+        }
+
+        match symbol.kind {
+            SymbolKind::Local if !index.is_referenced(id) => {
+                warn("unused_variable", format!("unused variable `{}`", symbol.name), symbol.start, symbol.end);
+            }
+            SymbolKind::Parameter if !index.is_referenced(id) && symbol.name != "self" => {
+                warn("unused_parameter", format!("parameter `{}` is never read", symbol.name), symbol.start, symbol.end);
+            }
+            SymbolKind::Function if !index.is_referenced(id) => {
+                warn("unused_function", format!("function `{}` is never used", symbol.name), symbol.start, symbol.end);
+            }
+            _ => {}
+        }
+    }
+
+    for flow in flows.iter().filter(|flow| flow.is_entry && !flow.always_returns) {
+        if let Some(symbol) = flow.symbol.map(|id| &index.symbols[id]) {
+            warn("missing_exit_code", format!("entry `{}` does not return an exit code on every path", symbol.name), symbol.start, symbol.end);
+        }
+    }
+
+    for (local, previous) in &index.shadows {
+        let symbol = &index.symbols[*local];
+        let previous = &index.symbols[*previous];
+        let kind = if previous.kind == SymbolKind::Parameter { "parameter" } else { "variable" };
+        warn("shadowed_name", format!("`{}` shadows a previous {} with the same name", symbol.name, kind), symbol.start, symbol.end);
+    }
+
+    // Statements following a return, break or continue in the parsed program
+    for (start, end) in &index.unreachable {
+        warn("unreachable_code", "unreachable code".to_owned(), *start, *end);
+    }
+
+    warnings.sort_by_key(|w| w.start_offset());
+    warnings
+}